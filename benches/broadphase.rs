@@ -0,0 +1,104 @@
+//! 碰撞判定粗筛的性能测试
+//!
+//! 模拟弹幕场景：窗口内分布着敌人与掩体格子，每个步长有数千道激光需要判定，
+//! 对比逐一比较所有目标与使用空间哈希两种方式。运行 `cargo bench --bench broadphase`。
+
+use bevy::{prelude::*, sprite::collide_aabb::collide};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use invaders::collision::SpatialHash;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// 窗口大小
+const WINDOW: Vec2 = Vec2::new(598., 676.);
+/// 敌人数量
+const ENEMIES: usize = 60;
+/// 掩体格子数量 (四座掩体)
+const BUNKER_CELLS: usize = 480;
+/// 每个步长的激光数量
+const LASER_COUNTS: [usize; 3] = [1_000, 2_500, 5_000];
+
+/// 包围盒：中心与大小
+type Aabb = (Vec2, Vec2);
+
+/// 在窗口内随机分布目标与激光
+fn scene(lasers: usize) -> (Vec<(Entity, Aabb)>, Vec<Aabb>) {
+    let mut rng = StdRng::seed_from_u64(7);
+
+    let mut targets = Vec::new();
+    for i in 0..ENEMIES {
+        let aabb = (random_point(&mut rng), Vec2::new(144., 75.) * 0.4);
+        targets.push((Entity::from_raw(i as u32), aabb));
+    }
+    for i in 0..BUNKER_CELLS {
+        let aabb = (random_point(&mut rng), Vec2::splat(5.));
+        targets.push((Entity::from_raw((ENEMIES + i) as u32), aabb));
+    }
+
+    let lasers = (0..lasers)
+        .map(|_| (random_point(&mut rng), Vec2::new(9., 54.) * 0.5))
+        .collect();
+    (targets, lasers)
+}
+
+/// 窗口内的随机位置
+fn random_point(rng: &mut StdRng) -> Vec2 {
+    Vec2::new(
+        rng.gen_range(-WINDOW.x / 2. ..WINDOW.x / 2.),
+        rng.gen_range(-WINDOW.y / 2. ..WINDOW.y / 2.),
+    )
+}
+
+/// 逐一比较每道激光与所有目标
+fn naive(targets: &[(Entity, Aabb)], lasers: &[Aabb]) -> usize {
+    let mut hits = 0;
+    for (laser_center, laser_size) in lasers {
+        for (_, (center, size)) in targets {
+            let collision = collide(
+                laser_center.extend(0.),
+                *laser_size,
+                center.extend(0.),
+                *size,
+            );
+            if collision.is_some() {
+                hits += 1;
+            }
+        }
+    }
+    hits
+}
+
+/// 重建空间哈希后只查询激光附近的格子，与游戏中每个步长的做法相同
+fn spatial_hash(hash: &mut SpatialHash, targets: &[(Entity, Aabb)], lasers: &[Aabb]) -> usize {
+    hash.clear();
+    for (entity, (center, size)) in targets {
+        hash.insert(*entity, *center, *size);
+    }
+    lasers
+        .iter()
+        .map(|(center, size)| hash.query(*center, *size).count())
+        .sum()
+}
+
+fn broadphase(c: &mut Criterion) {
+    let mut group = c.benchmark_group("broadphase");
+    for lasers in LASER_COUNTS {
+        let (targets, laser_boxes) = scene(lasers);
+        let mut hash = SpatialHash::default();
+        // 两种方式的命中数量必须一致
+        assert_eq!(
+            naive(&targets, &laser_boxes),
+            spatial_hash(&mut hash, &targets, &laser_boxes)
+        );
+
+        group.bench_with_input(BenchmarkId::new("naive", lasers), &lasers, |b, _| {
+            b.iter(|| naive(black_box(&targets), black_box(&laser_boxes)))
+        });
+        group.bench_with_input(BenchmarkId::new("spatial_hash", lasers), &lasers, |b, _| {
+            b.iter(|| spatial_hash(&mut hash, black_box(&targets), black_box(&laser_boxes)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, broadphase);
+criterion_main!(benches);
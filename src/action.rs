@@ -0,0 +1,260 @@
+use bevy::{
+    input::{
+        gamepad::{GamepadConnection, GamepadConnectionEvent},
+        InputSystem,
+    },
+    prelude::*,
+};
+
+use crate::config::GameConfig;
+
+/// 游戏中的逻辑动作
+///
+/// 键盘与手柄的按键先映射为动作，游戏系统只读取 `ActionState`，不直接读取按键
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// 向左移动、名字输入时光标左移
+    Left,
+    /// 向右移动、名字输入时光标右移
+    Right,
+    /// 名字输入时切换到下一个字母
+    Up,
+    /// 名字输入时切换到上一个字母
+    Down,
+    /// 攻击，按住蓄力
+    Fire,
+    /// 切换武器
+    SwitchWeapon,
+    /// 暂停
+    Pause,
+    /// 继续游戏
+    Resume,
+    /// 开始游戏、确认
+    Confirm,
+}
+
+impl Action {
+    /// 所有动作，数组下标即动作在掩码中的位
+    pub const ALL: [Action; 9] = [
+        Action::Left,
+        Action::Right,
+        Action::Up,
+        Action::Down,
+        Action::Fire,
+        Action::SwitchWeapon,
+        Action::Pause,
+        Action::Resume,
+        Action::Confirm,
+    ];
+
+    /// 动作在掩码中的位
+    fn mask(self) -> u16 {
+        1 << self as u16
+    }
+
+    /// 对应的键盘按键
+    pub fn keys(self) -> &'static [KeyCode] {
+        match self {
+            Action::Left => &[KeyCode::Left],
+            Action::Right => &[KeyCode::Right],
+            Action::Up => &[KeyCode::Up],
+            Action::Down => &[KeyCode::Down],
+            Action::Fire => &[KeyCode::Space],
+            Action::SwitchWeapon => &[KeyCode::W],
+            Action::Pause => &[KeyCode::P],
+            Action::Resume => &[KeyCode::R],
+            Action::Confirm => &[KeyCode::Return],
+        }
+    }
+
+    /// 对应的手柄按键
+    pub fn buttons(self) -> &'static [GamepadButtonType] {
+        use GamepadButtonType::*;
+
+        match self {
+            Action::Left => &[DPadLeft],
+            Action::Right => &[DPadRight],
+            Action::Up => &[DPadUp],
+            Action::Down => &[DPadDown],
+            Action::Fire => &[South, RightTrigger2],
+            Action::SwitchWeapon => &[North, RightTrigger],
+            Action::Pause | Action::Resume => &[Start],
+            Action::Confirm => &[South, Start],
+        }
+    }
+}
+
+/// 当前帧的动作状态
+///
+/// 每帧由键盘与当前手柄的输入合并而成，回放时由录像写入
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct ActionState {
+    /// 按住的动作掩码
+    pub(crate) pressed: u16,
+    /// 刚按下的动作掩码
+    pub(crate) just_pressed: u16,
+    /// 刚松开的动作掩码
+    pub(crate) just_released: u16,
+    /// 横向移动量，-1 到 1，摇杆推动的幅度决定移动速度
+    pub(crate) axis: f32,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed & action.mask() != 0
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed & action.mask() != 0
+    }
+
+    pub fn just_released(&self, action: Action) -> bool {
+        self.just_released & action.mask() != 0
+    }
+
+    /// 横向移动量，方向键优先，其次为左摇杆
+    pub fn axis(&self) -> f32 {
+        self.axis
+    }
+
+    /// 设置横向移动量，超出 -1 到 1 的部分被截断
+    pub fn set_axis(&mut self, axis: f32) {
+        self.axis = axis.clamp(-1., 1.);
+    }
+
+    /// 设置动作的状态，供机器人与测试在 `ActionSystem` 之后写入脚本输入
+    pub fn set(&mut self, action: Action, pressed: bool, just_pressed: bool, just_released: bool) {
+        for (mask, on) in [
+            (&mut self.pressed, pressed),
+            (&mut self.just_pressed, just_pressed),
+            (&mut self.just_released, just_released),
+        ] {
+            if on {
+                *mask |= action.mask();
+            } else {
+                *mask &= !action.mask();
+            }
+        }
+    }
+}
+
+/// 当前使用的手柄
+///
+/// 第一个连接的手柄作为当前手柄，断开后切换到其他已连接的手柄
+#[derive(Resource, Debug, Default)]
+pub struct ActiveGamepad {
+    pub gamepad: Option<Gamepad>,
+    /// 当前手柄在本帧断开，游戏中会自动暂停
+    disconnected: bool,
+}
+
+/// 动作输入系统集，在 `InputSystem` 之后运行，读取动作的系统需要在该集合之后
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ActionSystem;
+
+/// 输入插件，将键盘与手柄输入映射为 `ActionState`
+pub struct ActionPlugin;
+
+impl Plugin for ActionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActionState>()
+            .init_resource::<ActiveGamepad>()
+            .configure_set(
+                ActionSystem
+                    .after(InputSystem)
+                    .in_base_set(CoreSet::PreUpdate),
+            )
+            .add_systems(
+                (gamepad_connection_system, action_input_system)
+                    .chain()
+                    .in_set(ActionSystem),
+            );
+    }
+}
+
+/// 手柄热插拔系统
+fn gamepad_connection_system(
+    gamepads: Res<Gamepads>,
+    mut active: ResMut<ActiveGamepad>,
+    mut connection_events: EventReader<GamepadConnectionEvent>,
+) {
+    active.disconnected = false;
+
+    for event in connection_events.iter() {
+        match &event.connection {
+            GamepadConnection::Connected(info) => {
+                if active.gamepad.is_none() {
+                    info!("using gamepad {}: {}", event.gamepad.id, info.name);
+                    active.gamepad = Some(event.gamepad);
+                }
+            }
+            GamepadConnection::Disconnected => {
+                if active.gamepad != Some(event.gamepad) {
+                    continue;
+                }
+                active.gamepad = gamepads.iter().find(|gamepad| *gamepad != event.gamepad);
+                active.disconnected = true;
+                match active.gamepad {
+                    Some(gamepad) => info!(
+                        "gamepad {} disconnected, switching to gamepad {}",
+                        event.gamepad.id, gamepad.id
+                    ),
+                    None => info!("gamepad {} disconnected", event.gamepad.id),
+                }
+            }
+        }
+    }
+}
+
+/// 合并键盘与当前手柄的输入，更新动作状态
+fn action_input_system(
+    kb: Res<Input<KeyCode>>,
+    buttons: Res<Input<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    active: Res<ActiveGamepad>,
+    config: Res<GameConfig>,
+    mut actions: ResMut<ActionState>,
+) {
+    let gamepad_buttons = |action: Action| {
+        active.gamepad.into_iter().flat_map(move |gamepad| {
+            action
+                .buttons()
+                .iter()
+                .map(move |button_type| GamepadButton::new(gamepad, *button_type))
+        })
+    };
+
+    for action in Action::ALL {
+        let keys = action.keys().iter().copied();
+        let pressed = kb.any_pressed(keys.clone()) || buttons.any_pressed(gamepad_buttons(action));
+        let just_pressed =
+            kb.any_just_pressed(keys.clone()) || buttons.any_just_pressed(gamepad_buttons(action));
+        // 同一动作的其他按键仍按住时不算松开
+        let just_released = !pressed
+            && (kb.any_just_released(keys) || buttons.any_just_released(gamepad_buttons(action)));
+        actions.set(action, pressed, just_pressed, just_released);
+    }
+
+    // 当前手柄断开时暂停游戏
+    if active.disconnected {
+        actions.just_pressed |= Action::Pause.mask();
+    }
+
+    let stick = active
+        .gamepad
+        .and_then(|gamepad| axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX)))
+        .unwrap_or_default();
+    actions.axis = if actions.pressed(Action::Left) {
+        -1.
+    } else if actions.pressed(Action::Right) {
+        1.
+    } else {
+        apply_dead_zone(stick, config.gamepad.dead_zone)
+    };
+}
+
+/// 摇杆死区：死区内为 0，死区外的推动幅度重新映射到 0 到 1
+fn apply_dead_zone(value: f32, dead_zone: f32) -> f32 {
+    let magnitude = (value.abs() - dead_zone) / (1. - dead_zone);
+    magnitude.clamp(0., 1.).copysign(value)
+}
@@ -0,0 +1,148 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    collision::{Collider, Layers, SpatialHash},
+    components::{Laser, SpriteSize, Velocity},
+    config::GameConfig,
+    events::CollisionEvent,
+    resource::{GameRng, GameState, Playfield},
+    GameSet,
+};
+
+/// 掩体格子组件
+#[derive(Component)]
+pub struct BunkerCell;
+
+pub struct BunkerPlugin;
+
+impl Plugin for BunkerPlugin {
+    fn build(&self, app: &mut App) {
+        // 掩体判定在激光命中判定之前运行，被掩体挡住的激光不再命中后面的目标
+        app.add_system(bunker_spawn_system.in_schedule(OnExit(GameState::Welcome)))
+            .add_systems(
+                (bunker_hit_system, apply_system_buffers)
+                    .chain()
+                    .before(crate::player_laser_hit_enemy_system)
+                    .in_set(GameSet::Collision)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            );
+    }
+}
+
+/// 掩体生成系统，开始新的一局时在玩家上方均匀排列
+fn bunker_spawn_system(mut commands: Commands, config: Res<GameConfig>, playfield: Res<Playfield>) {
+    let bunkers = &config.bunkers;
+    let cell = bunkers.cell_size;
+    let rows = bunkers.shape.len();
+    let columns = bunkers.shape.iter().map(|row| row.chars().count()).max();
+    let Some(columns) = columns else {
+        return;
+    };
+
+    let (r, g, b) = bunkers.color;
+    let y = -playfield.h / 2. + bunkers.height;
+    for i in 0..bunkers.count {
+        let x = (i as f32 + 0.5) * playfield.w / bunkers.count as f32 - playfield.w / 2.;
+        // 左上角格子的中心
+        let origin = Vec2::new(
+            x - (columns - 1) as f32 * cell / 2.,
+            y + (rows - 1) as f32 * cell / 2.,
+        );
+        for (row, line) in bunkers.shape.iter().enumerate() {
+            for (column, _) in line.chars().enumerate().filter(|(_, c)| *c == '#') {
+                let position = origin + Vec2::new(column as f32 * cell, -(row as f32) * cell);
+                commands.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::rgb(r, g, b),
+                            custom_size: Some(Vec2::splat(cell)),
+                            ..Default::default()
+                        },
+                        transform: Transform::from_xyz(position.x, position.y, 5.),
+                        ..Default::default()
+                    },
+                    BunkerCell,
+                    SpriteSize(Vec2::splat(cell)),
+                    Collider::BUNKER,
+                ));
+            }
+        }
+    }
+}
+
+/// 掩体判定系统，处理弹道、敌人与掩体格子的碰撞事件
+///
+/// 任意一方的激光击中掩体时被挡下，并摧毁最先接触的格子及周围的部分格子，
+/// 敌人穿过掩体时摧毁接触到的格子
+fn bunker_hit_system(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    spatial_hash: Res<SpatialHash>,
+    laser_query: Query<&Velocity, With<Laser>>,
+    cell_query: Query<&Transform, With<BunkerCell>>,
+) {
+    let bunkers = &config.bunkers;
+    // 每道激光接触到的格子，按事件顺序记录
+    let mut contacts: Vec<(Entity, Vec<Entity>)> = Vec::new();
+    let mut crushed = Vec::new();
+    for event in collision_events.iter() {
+        if let Some((laser, cell)) = event.between(Layers::PROJECTILE, Layers::BUNKER) {
+            match contacts.iter_mut().find(|(entity, _)| *entity == laser) {
+                Some((_, cells)) => cells.push(cell),
+                None => contacts.push((laser, vec![cell])),
+            }
+        } else if let Some((_, cell)) = event.between(Layers::ENEMY, Layers::BUNKER) {
+            crushed.push(cell);
+        }
+    }
+
+    // 按判定顺序记录，销毁顺序不受哈希种子影响，保证回放与重玩一致
+    let mut destroyed: Vec<Entity> = Vec::new();
+    for (laser_entity, cells) in contacts {
+        let Ok(velocity) = laser_query.get(laser_entity) else {
+            continue;
+        };
+        // 激光的飞行方向上最先接触的格子
+        let direction = Vec2::new(velocity.x, velocity.y);
+        let hit = cells
+            .into_iter()
+            .filter(|entity| !destroyed.contains(entity))
+            .filter_map(|entity| cell_query.get(entity).ok())
+            .map(|cell_tf| cell_tf.translation.truncate())
+            .min_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction)));
+        let Some(impact) = hit else {
+            continue;
+        };
+
+        // 摧毁被击中的格子，波及范围内的格子有一半的概率被摧毁
+        let radius = bunkers.blast_radius.max(bunkers.cell_size / 2.);
+        let blast = Vec2::splat(radius * 2. + bunkers.cell_size);
+        for entity in spatial_hash.query(impact, blast) {
+            let Ok(cell_tf) = cell_query.get(entity) else {
+                continue;
+            };
+            let distance = cell_tf.translation.truncate().distance(impact);
+            if distance > radius || destroyed.contains(&entity) {
+                continue;
+            }
+            if distance < bunkers.cell_size / 2. || rng.gen_bool(0.5) {
+                destroyed.push(entity);
+            }
+        }
+        commands.entity(laser_entity).despawn_recursive();
+    }
+
+    // 敌人穿过掩体
+    for entity in crushed {
+        if !destroyed.contains(&entity) {
+            destroyed.push(entity);
+        }
+    }
+
+    for entity in destroyed {
+        commands.entity(entity).despawn();
+    }
+}
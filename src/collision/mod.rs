@@ -0,0 +1,347 @@
+use std::ops::BitOr;
+
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    components::SpriteSize, constants::SPATIAL_HASH_CELL_SIZE, events::CollisionEvent,
+    resource::GameState, GameSet,
+};
+use shape::{Body, ColliderShape};
+
+pub mod shape;
+
+pub struct CollisionPlugin;
+
+impl Plugin for CollisionPlugin {
+    fn build(&self, app: &mut App) {
+        // 在移动之后、碰撞判定之前检测，GameSet::Collision 中的系统只处理碰撞事件
+        app.init_resource::<SpatialHash>()
+            .add_event::<CollisionEvent>()
+            .add_system(
+                collision_detection_system
+                    .after(GameSet::Movement)
+                    .before(GameSet::Collision)
+                    .run_if(in_state(GameState::InGame))
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            );
+    }
+}
+
+/// 碰撞层，每一位代表一类实体
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Layers(u32);
+
+impl Layers {
+    pub const NONE: Self = Self(0);
+    pub const PLAYER: Self = Self(1 << 0);
+    pub const ENEMY: Self = Self(1 << 1);
+    pub const PLAYER_PROJECTILE: Self = Self(1 << 2);
+    pub const ENEMY_PROJECTILE: Self = Self(1 << 3);
+    pub const PICKUP: Self = Self(1 << 4);
+    pub const BUNKER: Self = Self(1 << 5);
+    /// 双方的弹道
+    pub const PROJECTILE: Self = Self::PLAYER_PROJECTILE.union(Self::ENEMY_PROJECTILE);
+
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// 是否有共同的层
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for Layers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+/// 碰撞体组件
+///
+/// `layer` 为实体所在的层，`mask` 为需要检测的层，双方的 `mask` 都包含对方所在的层时才会发生碰撞。
+/// 包围盒的大小取自 `SpriteSize` 与缩放
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Collider {
+    pub layer: Layers,
+    pub mask: Layers,
+}
+
+impl Collider {
+    pub const PLAYER: Self = Self::new(
+        Layers::PLAYER,
+        Layers::ENEMY
+            .union(Layers::ENEMY_PROJECTILE)
+            .union(Layers::PICKUP),
+    );
+    pub const ENEMY: Self = Self::new(
+        Layers::ENEMY,
+        Layers::PLAYER
+            .union(Layers::PLAYER_PROJECTILE)
+            .union(Layers::BUNKER),
+    );
+    pub const PLAYER_PROJECTILE: Self = Self::new(
+        Layers::PLAYER_PROJECTILE,
+        Layers::ENEMY.union(Layers::BUNKER),
+    );
+    pub const ENEMY_PROJECTILE: Self = Self::new(
+        Layers::ENEMY_PROJECTILE,
+        Layers::PLAYER.union(Layers::BUNKER),
+    );
+    pub const PICKUP: Self = Self::new(Layers::PICKUP, Layers::PLAYER);
+    pub const BUNKER: Self = Self::new(Layers::BUNKER, Layers::PROJECTILE.union(Layers::ENEMY));
+
+    pub const fn new(layer: Layers, mask: Layers) -> Self {
+        Self { layer, mask }
+    }
+
+    /// 是否与另一个碰撞体发生碰撞
+    pub fn interacts(&self, other: &Collider) -> bool {
+        self.mask.intersects(other.layer) && other.mask.intersects(self.layer)
+    }
+}
+
+/// 空间哈希资源，碰撞判定的粗筛阶段
+///
+/// 把实体的包围盒登记到所覆盖的格子中，查询时只检查与查询范围重叠的格子，
+/// 避免逐一比较所有激光与所有目标
+#[derive(Resource)]
+pub struct SpatialHash {
+    cell_size: f32,
+    /// 格子坐标到登记条目下标的映射，清空时保留已分配的空间
+    cells: HashMap<(i32, i32), Vec<usize>>,
+    entries: Vec<Entry>,
+}
+
+/// 登记在空间哈希中的包围盒
+struct Entry {
+    entity: Entity,
+    min: Vec2,
+    max: Vec2,
+}
+
+impl Default for SpatialHash {
+    fn default() -> Self {
+        Self::new(SPATIAL_HASH_CELL_SIZE)
+    }
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// 清空所有登记的实体
+    pub fn clear(&mut self) {
+        for indices in self.cells.values_mut() {
+            indices.clear();
+        }
+        self.entries.clear();
+    }
+
+    /// 登记的实体数量
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 登记实体，`center` 与 `size` 为包围盒的中心与大小
+    pub fn insert(&mut self, entity: Entity, center: Vec2, size: Vec2) {
+        let (min, max) = (center - size / 2., center + size / 2.);
+        let index = self.entries.len();
+        self.entries.push(Entry { entity, min, max });
+
+        let ((x0, y0), (x1, y1)) = (self.cell(min), self.cell(max));
+        for x in x0..=x1 {
+            for y in y0..=y1 {
+                self.cells.entry((x, y)).or_default().push(index);
+            }
+        }
+    }
+
+    /// 查询包围盒与给定范围重叠的实体，每个实体只返回一次
+    pub fn query(&self, center: Vec2, size: Vec2) -> impl Iterator<Item = Entity> + '_ {
+        let (min, max) = (center - size / 2., center + size / 2.);
+        let ((x0, y0), (x1, y1)) = (self.cell(min), self.cell(max));
+
+        (x0..=x1)
+            .flat_map(move |x| (y0..=y1).map(move |y| (x, y)))
+            .filter_map(|cell| Some(cell).zip(self.cells.get(&cell)))
+            .flat_map(move |(cell, indices)| {
+                indices
+                    .iter()
+                    .map(move |&index| (cell, &self.entries[index]))
+            })
+            .filter(move |(cell, entry)| {
+                // 覆盖多个格子的实体只在重叠区域左下角所在的格子中返回，不需要额外去重
+                let overlaps = entry.min.x < max.x
+                    && entry.max.x > min.x
+                    && entry.min.y < max.y
+                    && entry.max.y > min.y;
+                overlaps && *cell == self.cell(entry.min.max(min))
+            })
+            .map(|(_, entry)| entry.entity)
+    }
+
+    /// 坐标所在的格子
+    fn cell(&self, point: Vec2) -> (i32, i32) {
+        let cell = (point / self.cell_size).floor();
+        (cell.x as i32, cell.y as i32)
+    }
+}
+
+/// 碰撞检测系统
+///
+/// 重建空间哈希，对包围盒重叠、碰撞层互相匹配且形状精确重叠的每一对实体发送一次碰撞事件。
+/// 新的实体类型只需要添加 `Collider`，不需要新的检测代码
+#[allow(clippy::type_complexity)]
+fn collision_detection_system(
+    mut spatial_hash: ResMut<SpatialHash>,
+    mut collision_events: EventWriter<CollisionEvent>,
+    query: Query<(
+        Entity,
+        &Transform,
+        &SpriteSize,
+        &Collider,
+        Option<&ColliderShape>,
+    )>,
+) {
+    static AABB: ColliderShape = ColliderShape::Aabb;
+
+    spatial_hash.clear();
+    for (entity, tf, size, _, shape) in query.iter() {
+        let body = Body::new(tf, size, shape.unwrap_or(&AABB));
+        spatial_hash.insert(entity, body.center(), body.bounds());
+    }
+
+    // 每一对只由所在层较低的一方发送 (同层时由编号较小的一方发送)，
+    // 数量最多的掩体格子因此不需要查询
+    let order = |entity: Entity, collider: &Collider| (collider.layer.0, entity);
+    for (entity, tf, size, collider, shape) in query.iter() {
+        if collider.mask.0 < collider.layer.0 {
+            continue;
+        }
+        let body = Body::new(tf, size, shape.unwrap_or(&AABB));
+        for other in spatial_hash.query(body.center(), body.bounds()) {
+            let Ok((_, other_tf, other_size, other_collider, other_shape)) = query.get(other)
+            else {
+                continue;
+            };
+            if order(other, other_collider) <= order(entity, collider)
+                || !collider.interacts(other_collider)
+            {
+                continue;
+            }
+            // 包围盒重叠后按形状精确判定
+            let other_body = Body::new(other_tf, other_size, other_shape.unwrap_or(&AABB));
+            if !body.intersects(&other_body) {
+                continue;
+            }
+            collision_events.send(CollisionEvent {
+                a: entity,
+                a_layer: collider.layer,
+                b: other,
+                b_layer: other_collider.layer,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 登记一组包围盒，返回查询结果与逐个比较得到的结果 (均已排序)
+    fn query_both(boxes: &[(Vec2, Vec2)], center: Vec2, size: Vec2) -> (Vec<Entity>, Vec<Entity>) {
+        let mut spatial_hash = SpatialHash::new(10.);
+        for (i, (box_center, box_size)) in boxes.iter().enumerate() {
+            spatial_hash.insert(Entity::from_raw(i as u32), *box_center, *box_size);
+        }
+
+        let mut found: Vec<_> = spatial_hash.query(center, size).collect();
+        found.sort();
+
+        let (min, max) = (center - size / 2., center + size / 2.);
+        let expected = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, (box_center, box_size))| {
+                let (box_min, box_max) =
+                    (*box_center - *box_size / 2., *box_center + *box_size / 2.);
+                box_min.x < max.x && box_max.x > min.x && box_min.y < max.y && box_max.y > min.y
+            })
+            .map(|(i, _)| Entity::from_raw(i as u32))
+            .collect();
+        (found, expected)
+    }
+
+    #[test]
+    fn large_entities_are_returned_once() {
+        // 分别覆盖 3x3、2x2、4x1 个格子
+        let boxes = [
+            (Vec2::new(15., 15.), Vec2::new(25., 25.)),
+            (Vec2::new(10., 10.), Vec2::new(10., 10.)),
+            (Vec2::new(20., 5.), Vec2::new(35., 4.)),
+        ];
+        for (center, size) in [
+            (Vec2::new(15., 15.), Vec2::new(30., 30.)),
+            (Vec2::new(12., 12.), Vec2::new(2., 2.)),
+            (Vec2::new(25., 8.), Vec2::new(20., 10.)),
+        ] {
+            let (found, expected) = query_both(&boxes, center, size);
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn queries_along_cell_boundaries() {
+        let boxes = [
+            (Vec2::new(5., 5.), Vec2::new(10., 10.)),
+            (Vec2::new(15., 5.), Vec2::new(10., 10.)),
+            (Vec2::new(10., 10.), Vec2::new(4., 4.)),
+            (Vec2::new(20., 20.), Vec2::new(2., 2.)),
+        ];
+        for (center, size) in [
+            // 与格子完全重合
+            (Vec2::new(5., 5.), Vec2::new(10., 10.)),
+            (Vec2::new(15., 5.), Vec2::new(10., 10.)),
+            // 以格子交点为中心
+            (Vec2::new(10., 10.), Vec2::new(20., 20.)),
+            // 宽度为 0 的竖线正好落在格子边界上
+            (Vec2::new(10., 5.), Vec2::new(0., 10.)),
+        ] {
+            let (found, expected) = query_both(&boxes, center, size);
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn negative_coordinates() {
+        let boxes = [
+            (Vec2::new(-5., -5.), Vec2::new(10., 10.)),
+            (Vec2::new(0., 0.), Vec2::new(30., 30.)),
+            (Vec2::new(-25., 3.), Vec2::new(12., 2.)),
+            (Vec2::new(-0.5, -19.5), Vec2::new(1., 1.)),
+        ];
+        for (center, size) in [
+            (Vec2::new(-10., -10.), Vec2::new(20., 20.)),
+            (Vec2::new(-20., 0.), Vec2::new(10., 10.)),
+            (Vec2::new(0., -20.), Vec2::new(2., 2.)),
+            (Vec2::ZERO, Vec2::new(60., 60.)),
+        ] {
+            let (found, expected) = query_both(&boxes, center, size);
+            assert!(!expected.is_empty());
+            assert_eq!(found, expected);
+        }
+    }
+}
@@ -0,0 +1,329 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use bevy::{
+    asset::FileAssetIo,
+    math::Vec3Swizzles,
+    prelude::*,
+    render::texture::{CompressedImageFormats, ImageType},
+    utils::HashMap,
+};
+
+use crate::{
+    components::SpriteSize,
+    config::{GameConfig, ShapeConfig, SpriteConfig},
+    constants::MASK_ALPHA_THRESHOLD,
+    enemy::kind::EnemyKind,
+};
+
+/// 碰撞形状组件
+///
+/// 空间哈希粗筛出包围盒重叠的实体后，按双方的形状精确判定。
+/// 没有该组件的碰撞体按 `SpriteSize` 大小、不随旋转的矩形判定
+#[derive(Component, Clone, Debug, Default)]
+pub enum ColliderShape {
+    /// 图片大小的矩形，不随实体旋转
+    #[default]
+    Aabb,
+    /// 以图片中心为圆心的圆，半径以图片像素为单位
+    Circle(f32),
+    /// 图片大小的矩形，随实体旋转
+    Obb,
+    /// 图片中不透明的像素，`flip_y` 与图片的上下翻转一致
+    Mask { mask: Arc<PixelMask>, flip_y: bool },
+}
+
+impl ColliderShape {
+    /// 不需要图片数据的形状，像素遮罩需要通过 `ColliderShapes` 获取，这里按矩形处理
+    pub fn geometric(shape: ShapeConfig) -> Self {
+        match shape {
+            ShapeConfig::Aabb | ShapeConfig::Mask => Self::Aabb,
+            ShapeConfig::Circle(radius) => Self::Circle(radius),
+            ShapeConfig::Obb => Self::Obb,
+        }
+    }
+
+    /// 上下翻转，只影响像素遮罩
+    pub fn flip_y(self, flip: bool) -> Self {
+        match self {
+            Self::Mask { mask, flip_y } => Self::Mask {
+                mask,
+                flip_y: flip_y ^ flip,
+            },
+            shape => shape,
+        }
+    }
+}
+
+/// 像素遮罩，记录图片中每个像素是否不透明
+#[derive(Debug)]
+pub struct PixelMask {
+    width: usize,
+    height: usize,
+    /// 按行从上到下排列
+    opaque: Vec<bool>,
+}
+
+impl PixelMask {
+    /// 从图片文件的透明通道生成遮罩
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let invalid = |reason: String| io::Error::new(io::ErrorKind::InvalidData, reason);
+
+        let bytes = fs::read(path)?;
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default();
+        let image = Image::from_buffer(
+            &bytes,
+            ImageType::Extension(extension),
+            CompressedImageFormats::NONE,
+            true,
+        )
+        .map_err(|err| invalid(err.to_string()))?;
+
+        let size = image.texture_descriptor.size;
+        let (width, height) = (size.width as usize, size.height as usize);
+        // 只支持每个像素 4 字节的 RGBA 图片
+        if image.data.len() != width * height * 4 {
+            return Err(invalid(format!(
+                "unsupported pixel format {:?}",
+                image.texture_descriptor.format
+            )));
+        }
+
+        let opaque = image
+            .data
+            .chunks_exact(4)
+            .map(|pixel| pixel[3] >= MASK_ALPHA_THRESHOLD)
+            .collect();
+        Ok(Self {
+            width,
+            height,
+            opaque,
+        })
+    }
+
+    /// 图片的像素大小
+    pub fn size(&self) -> Vec2 {
+        Vec2::new(self.width as f32, self.height as f32)
+    }
+
+    /// 像素是否不透明，`(x, y)` 从左上角开始计算
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.opaque[y * self.width + x]
+    }
+}
+
+/// 碰撞形状资源
+///
+/// 启动时为使用像素遮罩的图片生成遮罩，无窗口模式同样从图片文件生成，保证回放时的碰撞结果一致
+#[derive(Resource, Default)]
+pub struct ColliderShapes {
+    /// 图片路径到遮罩的映射
+    masks: HashMap<String, Arc<PixelMask>>,
+}
+
+impl ColliderShapes {
+    /// 读取配置中所有使用像素遮罩的图片，无法读取的图片按矩形处理
+    pub fn load(config: &GameConfig) -> Self {
+        let sprites = [&config.sprites.player, &config.boss.sprite]
+            .into_iter()
+            .chain(EnemyKind::ALL.map(|kind| &config.enemies.get(kind).sprite));
+
+        let mut masks = HashMap::new();
+        for sprite in sprites {
+            if sprite.shape != ShapeConfig::Mask || masks.contains_key(&sprite.path) {
+                continue;
+            }
+            match PixelMask::load(&asset_path(&sprite.path)) {
+                Ok(mask) => {
+                    masks.insert(sprite.path.clone(), Arc::new(mask));
+                }
+                Err(err) => {
+                    warn!(
+                        "failed to load pixel mask {}, using a rectangle: {err}",
+                        sprite.path
+                    )
+                }
+            }
+        }
+        Self { masks }
+    }
+
+    /// 图片对应的碰撞形状
+    pub fn get(&self, sprite: &SpriteConfig) -> ColliderShape {
+        match (sprite.shape, self.masks.get(&sprite.path)) {
+            (ShapeConfig::Mask, Some(mask)) => ColliderShape::Mask {
+                mask: mask.clone(),
+                flip_y: false,
+            },
+            (shape, _) => ColliderShape::geometric(shape),
+        }
+    }
+}
+
+/// 资源文件的路径，与 AssetServer 使用相同的根目录
+fn asset_path(path: &str) -> PathBuf {
+    FileAssetIo::get_base_path().join("assets").join(path)
+}
+
+/// 世界坐标中的碰撞体，用于精确判定
+pub(crate) struct Body<'a> {
+    center: Vec2,
+    /// 实体 x 轴旋转后的方向
+    axis: Vec2,
+    /// 缩放后的半宽与半高，圆形两者都为半径
+    half: Vec2,
+    shape: &'a ColliderShape,
+}
+
+impl<'a> Body<'a> {
+    pub(crate) fn new(tf: &Transform, size: &SpriteSize, shape: &'a ColliderShape) -> Self {
+        let scale = tf.scale.xy().abs();
+        let rotated = (tf.rotation * Vec3::X).xy().normalize_or_zero();
+        let (axis, half) = match shape {
+            ColliderShape::Aabb => (Vec2::X, size.0 * scale / 2.),
+            ColliderShape::Circle(radius) => (Vec2::X, Vec2::splat(radius * scale.max_element())),
+            ColliderShape::Obb => (rotated, size.0 * scale / 2.),
+            // 图片按原始像素大小显示，遮罩不使用 SpriteSize
+            ColliderShape::Mask { mask, .. } => (rotated, mask.size() * scale / 2.),
+        };
+        Self {
+            center: tf.translation.xy(),
+            axis: if axis == Vec2::ZERO { Vec2::X } else { axis },
+            half,
+            shape,
+        }
+    }
+
+    pub(crate) fn center(&self) -> Vec2 {
+        self.center
+    }
+
+    /// 包围盒的大小，用于空间哈希粗筛
+    pub(crate) fn bounds(&self) -> Vec2 {
+        let (cos, sin) = (self.axis.x.abs(), self.axis.y.abs());
+        Vec2::new(
+            cos * self.half.x + sin * self.half.y,
+            sin * self.half.x + cos * self.half.y,
+        ) * 2.
+    }
+
+    /// 两个碰撞体是否重叠
+    pub(crate) fn intersects(&self, other: &Body) -> bool {
+        use ColliderShape::*;
+
+        match (self.shape, other.shape) {
+            (Mask { .. }, _) => self.mask_overlaps(other),
+            (_, Mask { .. }) => other.mask_overlaps(self),
+            (Circle(_), Circle(_)) => {
+                self.center.distance_squared(other.center) <= (self.half.x + other.half.x).powi(2)
+            }
+            (Circle(_), _) => other.box_overlaps_circle(self),
+            (_, Circle(_)) => self.box_overlaps_circle(other),
+            _ => self.box_overlaps_box(other),
+        }
+    }
+
+    /// 世界坐标中的点是否在碰撞体内
+    fn contains(&self, point: Vec2) -> bool {
+        let local = self.to_local(point);
+        match self.shape {
+            ColliderShape::Circle(_) => local.length_squared() <= self.half.x.powi(2),
+            ColliderShape::Mask { mask, flip_y } => {
+                let pixel = self.to_pixel(local, mask, *flip_y);
+                local.abs().cmple(self.half).all()
+                    && mask.get(pixel.x.max(0.) as usize, pixel.y.max(0.) as usize)
+            }
+            ColliderShape::Aabb | ColliderShape::Obb => local.abs().cmple(self.half).all(),
+        }
+    }
+
+    /// 世界坐标转换为以碰撞体中心为原点、未旋转的坐标
+    fn to_local(&self, point: Vec2) -> Vec2 {
+        let offset = point - self.center;
+        Vec2::new(offset.dot(self.axis), offset.dot(self.axis.perp()))
+    }
+
+    /// 未旋转的坐标转换为遮罩的像素坐标 (从左上角开始计算)
+    fn to_pixel(&self, local: Vec2, mask: &PixelMask, flip_y: bool) -> Vec2 {
+        let mut uv = Vec2::new(
+            (local.x + self.half.x) / (self.half.x * 2.),
+            (self.half.y - local.y) / (self.half.y * 2.),
+        );
+        if flip_y {
+            uv.y = 1. - uv.y;
+        }
+        uv * mask.size()
+    }
+
+    /// 矩形与圆形的判定：矩形内离圆心最近的点在圆内
+    fn box_overlaps_circle(&self, circle: &Body) -> bool {
+        let local = self.to_local(circle.center);
+        let closest = local.clamp(-self.half, self.half);
+        local.distance_squared(closest) <= circle.half.x.powi(2)
+    }
+
+    /// 矩形与矩形的判定：分离轴定理，四条边的法线上投影都重叠时相交
+    fn box_overlaps_box(&self, other: &Body) -> bool {
+        let offset = other.center - self.center;
+        [self.axis, self.axis.perp(), other.axis, other.axis.perp()]
+            .into_iter()
+            .all(|axis| {
+                offset.dot(axis).abs() <= self.projected_radius(axis) + other.projected_radius(axis)
+            })
+    }
+
+    /// 矩形在轴上投影长度的一半
+    fn projected_radius(&self, axis: Vec2) -> f32 {
+        self.half.x * self.axis.dot(axis).abs() + self.half.y * self.axis.perp().dot(axis).abs()
+    }
+
+    /// 像素遮罩的判定：双方包围盒重叠区域内，遮罩中的不透明像素的中心落在对方内部
+    fn mask_overlaps(&self, other: &Body) -> bool {
+        let ColliderShape::Mask { mask, flip_y } = self.shape else {
+            return self.box_overlaps_box(other);
+        };
+
+        let (bounds, other_bounds) = (self.bounds() / 2., other.bounds() / 2.);
+        let min = (self.center - bounds).max(other.center - other_bounds);
+        let max = (self.center + bounds).min(other.center + other_bounds);
+        if min.x > max.x || min.y > max.y {
+            return false;
+        }
+
+        // 重叠区域在遮罩中覆盖的像素范围
+        let corners = [min, max, Vec2::new(min.x, max.y), Vec2::new(max.x, min.y)]
+            .map(|corner| self.to_pixel(self.to_local(corner), mask, *flip_y));
+        let low = corners.into_iter().reduce(Vec2::min).unwrap_or_default();
+        let high = corners.into_iter().reduce(Vec2::max).unwrap_or_default();
+        let low = low.floor().max(Vec2::ZERO);
+        let high = high.ceil().min(mask.size());
+
+        for y in low.y as usize..high.y as usize {
+            for x in low.x as usize..high.x as usize {
+                if !mask.get(x, y) {
+                    continue;
+                }
+                // 像素中心转换回世界坐标
+                let mut uv = (Vec2::new(x as f32, y as f32) + 0.5) / mask.size();
+                if *flip_y {
+                    uv.y = 1. - uv.y;
+                }
+                let local = Vec2::new(
+                    uv.x * self.half.x * 2. - self.half.x,
+                    self.half.y - uv.y * self.half.y * 2.,
+                );
+                let point = self.center + self.axis * local.x + self.axis.perp() * local.y;
+                if other.contains(point) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
@@ -1,8 +1,10 @@
 use bevy::{
-    prelude::{Component, Vec2, Vec3},
+    prelude::{Color, Component, Entity, Vec2, Vec3},
     time::{Timer, TimerMode},
 };
 
+use crate::{collision::Collider, constants::HIT_FLASH_SECONDS};
+
 // 通用控制组件
 #[derive(Component)]
 pub struct Velocity {
@@ -27,15 +29,145 @@ pub struct Movable {
 pub struct Player;
 
 /// 玩家信息组件
-#[derive(Component)]
+#[derive(Component, Clone, Copy)]
 pub struct FromPlayer;
 
+/// 弹道所属的阵营
+pub trait Team: Component + Copy {
+    /// 该阵营弹道的碰撞体
+    const COLLIDER: Collider;
+}
+
+impl Team for FromPlayer {
+    const COLLIDER: Collider = Collider::PLAYER_PROJECTILE;
+}
+
+impl Team for FromEnemy {
+    const COLLIDER: Collider = Collider::ENEMY_PROJECTILE;
+}
+
 /// 敌人组件
 #[derive(Component)]
 pub struct Enemy;
 
-/// 敌人信息组件
+/// 生命值组件
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Health {
+    /// 当前生命值
+    pub current: u32,
+    /// 最大生命值
+    pub max: u32,
+}
+
+impl Health {
+    pub fn new(max: u32) -> Self {
+        Self { current: max, max }
+    }
+
+    /// 受到伤害，返回生命值是否耗尽
+    pub fn damage(&mut self, amount: u32) -> bool {
+        self.current = self.current.saturating_sub(amount);
+        self.current == 0
+    }
+}
+
+/// 伤害组件，命中目标时扣除的生命值
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Damage(pub u32);
+
+/// 穿透组件，弹道命中目标后继续飞行
+#[derive(Component)]
+pub struct Piercing {
+    /// 剩余可穿透的目标数量
+    pub remaining: u32,
+    /// 已命中的目标，同一目标只命中一次
+    pub hit: Vec<Entity>,
+}
+
+impl Piercing {
+    pub fn new(remaining: u32) -> Self {
+        Self {
+            remaining,
+            hit: Vec::new(),
+        }
+    }
+
+    /// 是否已经命中过目标
+    pub fn has_hit(&self, target: Entity) -> bool {
+        self.hit.contains(&target)
+    }
+
+    /// 记录命中的目标，返回弹道是否继续飞行
+    pub fn pierce(&mut self, target: Entity) -> bool {
+        self.hit.push(target);
+        if self.remaining == 0 {
+            return false;
+        }
+        self.remaining -= 1;
+        true
+    }
+}
+
+/// 追踪组件
 #[derive(Component)]
+pub struct Homing {
+    /// 每秒最大转向角度 (弧度)
+    pub turn_rate: f32,
+}
+
+/// 攻击状态组件，记录武器冷却与蓄力时间
+#[derive(Component)]
+pub struct FireControl {
+    /// 武器冷却计时，结束后才能再次发射
+    pub cooldown: Timer,
+    /// 已蓄力时间 (秒)
+    pub charge: f32,
+}
+
+impl Default for FireControl {
+    fn default() -> Self {
+        Self {
+            cooldown: Timer::from_seconds(0., TimerMode::Once),
+            charge: 0.,
+        }
+    }
+}
+
+impl FireControl {
+    /// 冷却是否结束
+    pub fn ready(&self) -> bool {
+        self.cooldown.finished()
+    }
+
+    /// 发射后开始冷却
+    pub fn start_cooldown(&mut self, seconds: f32) {
+        self.cooldown = Timer::from_seconds(seconds, TimerMode::Once);
+    }
+}
+
+/// 撞击冷却组件，记录可以再次与玩家相撞的时刻 (秒)
+#[derive(Component)]
+pub struct ContactCooldown(pub f64);
+
+/// 受击闪烁组件，计时结束后恢复原来的颜色
+#[derive(Component)]
+pub struct HitFlash {
+    pub timer: Timer,
+    /// 闪烁前的颜色
+    pub color: Color,
+}
+
+impl HitFlash {
+    pub fn new(color: Color) -> Self {
+        Self {
+            timer: Timer::from_seconds(HIT_FLASH_SECONDS, TimerMode::Once),
+            color,
+        }
+    }
+}
+
+/// 敌人信息组件
+#[derive(Component, Clone, Copy)]
 pub struct FromEnemy;
 
 /// 激光组件
@@ -75,6 +207,38 @@ impl Default for ExplosionTimer {
 #[derive(Component)]
 pub struct DisplayScore;
 
+/// HUD 定位组件
+///
+/// `anchor` 为窗口可见区域中的相对位置，x、y 取值 -1 到 1，例如 (0, 1) 为上边缘中点，
+/// `offset` 为相对锚点的偏移。窗口大小改变时由 `hud_anchor_system` 重新计算位置
+#[derive(Component, Clone, Copy, Debug)]
+pub struct HudAnchor {
+    pub anchor: Vec2,
+    pub offset: Vec2,
+}
+
+impl HudAnchor {
+    pub fn new(anchor: Vec2, offset: Vec2) -> Self {
+        Self { anchor, offset }
+    }
+}
+
+/// 黑边组件，遮挡游戏区域以外的部分
+#[derive(Component)]
+pub struct Letterbox;
+
+/// 道具状态组件
+#[derive(Component)]
+pub struct PowerUpText;
+
+/// 武器状态组件
+#[derive(Component)]
+pub struct WeaponText;
+
+/// 护盾条组件
+#[derive(Component)]
+pub struct ShieldBar;
+
 /// 欢迎组件
 #[derive(Component)]
 pub struct WelcomeText;
@@ -82,3 +246,19 @@ pub struct WelcomeText;
 /// 暂停组件
 #[derive(Component)]
 pub struct PausedText;
+
+/// 游戏结束组件
+#[derive(Component)]
+pub struct GameOverText;
+
+/// 排行榜组件
+#[derive(Component)]
+pub struct HighScoreText;
+
+/// 名字输入组件
+#[derive(Component)]
+pub struct NameEntryText;
+
+/// 波次横幅组件
+#[derive(Component)]
+pub struct WaveBanner;
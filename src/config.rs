@@ -0,0 +1,1293 @@
+use std::{
+    error::Error,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::*,
+    enemy::{kind::EnemyKind, pattern::FirePattern, wave::FormationKind},
+    powerup::PowerUpKind,
+    weapon::{Weapon, WeaponKind},
+};
+
+/// 默认配置文件路径，存在时在启动时读取
+pub const DEFAULT_CONFIG_PATH: &str = "invaders.ron";
+
+/// 游戏配置资源
+///
+/// 启动时从 RON 文件读取，文件中缺省的字段使用 `constants.rs` 中的默认值
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    /// 游戏模式
+    pub mode: GameMode,
+    /// 游戏区域大小，同时作为窗口的初始大小 (无窗口模式下作为固定的 WinSize)
+    pub window_size: (f32, f32),
+    /// 固定步长 (秒)
+    pub time_step: f32,
+    /// 基础速度
+    pub base_speed: f32,
+    /// 图片缩放比例
+    pub sprite_scale: f32,
+    /// 第一波同时存在的敌人最大数量
+    pub max_enemy: u32,
+    /// 第一波阵型内敌人最大数量
+    pub formation_member_max: u32,
+    /// 第一波敌人平均每秒攻击次数
+    pub enemy_fire_rate: f64,
+    /// 玩家初始生命数
+    pub player_lives: u32,
+    /// 玩家每条生命的护盾值
+    pub player_hit_points: u32,
+    /// 玩家自动重生时间 (秒)
+    pub player_respawn_delay: f64,
+    /// 波次横幅展示时间 (秒)
+    pub wave_banner_seconds: f32,
+    /// 图片资源
+    pub sprites: SpritesConfig,
+    /// 各类武器的属性
+    pub weapons: WeaponsConfig,
+    /// 蓄力射击
+    pub charge_shot: ChargeShotConfig,
+    /// 各类敌人的属性
+    pub enemies: EnemiesConfig,
+    /// Boss
+    pub boss: BossConfig,
+    /// 机体碰撞
+    pub collision: CollisionConfig,
+    /// 网格模式
+    pub grid: GridConfig,
+    /// 掩体
+    pub bunkers: BunkersConfig,
+    /// 道具掉落与效果
+    pub power_ups: PowerUpsConfig,
+    /// 手柄
+    pub gamepad: GamepadConfig,
+    /// 声音资源
+    pub audios: AudiosConfig,
+    /// 字体路径
+    pub font: String,
+}
+
+/// 图片资源配置
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpritesConfig {
+    /// 游戏背景图片路径
+    pub background: String,
+    /// 玩家
+    pub player: SpriteConfig,
+    /// 爆炸动画
+    pub explosion: ExplosionConfig,
+}
+
+/// 单个图片配置
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpriteConfig {
+    /// 图片路径
+    pub path: String,
+    /// 图片大小
+    pub size: (f32, f32),
+    /// 碰撞形状
+    #[serde(default)]
+    pub shape: ShapeConfig,
+}
+
+/// 碰撞形状配置
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ShapeConfig {
+    /// 图片大小的矩形，不随实体旋转
+    #[default]
+    Aabb,
+    /// 以图片中心为圆心的圆，半径以图片像素为单位
+    Circle(f32),
+    /// 图片大小的矩形，随实体旋转
+    Obb,
+    /// 图片中不透明的像素，由图片的透明通道生成，大小取图片本身的像素大小
+    Mask,
+}
+
+/// 爆炸动画配置
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExplosionConfig {
+    /// 图片路径
+    pub path: String,
+    /// 单帧大小
+    pub size: (f32, f32),
+    /// 列数
+    pub columns: usize,
+    /// 行数
+    pub rows: usize,
+    /// 画面帧数
+    pub frames: usize,
+}
+
+/// 各类武器的属性配置
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WeaponsConfig {
+    /// 双发激光
+    pub twin: Weapon,
+    /// 扇形散射
+    pub spread: Weapon,
+    /// 穿透光束
+    pub beam: Weapon,
+    /// 追踪导弹
+    pub homing: Weapon,
+    /// 敌人单发激光
+    pub enemy_single: Weapon,
+    /// 敌人双发激光
+    pub enemy_twin: Weapon,
+}
+
+/// Boss 配置
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BossConfig {
+    /// 末尾出现 Boss 的波次
+    pub waves: Vec<u32>,
+    /// 每隔多少波出现一次 Boss，为 0 时只在 `waves` 中的波次出现
+    pub every: u32,
+    /// 图片
+    pub sprite: SpriteConfig,
+    /// 图片颜色 (r, g, b)
+    pub tint: (f32, f32, f32),
+    /// 相对 `sprite_scale` 的缩放比例
+    pub scale: f32,
+    /// 生命值
+    pub hit_points: u32,
+    /// 被消灭时的得分
+    pub score: u32,
+    /// 相对波次速度的倍数
+    pub speed_scale: f32,
+    /// 与玩家机体相撞时对玩家造成的伤害
+    pub contact_damage: u32,
+    /// 武器
+    pub weapon: WeaponKind,
+    /// 受击区域，只有击中这些区域才会造成伤害
+    pub zones: Vec<HitZoneConfig>,
+    /// 各阶段的行为，按顺序排列，生命值比例降到 `health` 以下时进入该阶段
+    pub phases: Vec<BossPhaseConfig>,
+}
+
+/// Boss 的受击区域，坐标与大小均为缩放前的图片像素
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HitZoneConfig {
+    /// 相对图片中心的偏移
+    pub offset: (f32, f32),
+    /// 大小
+    pub size: (f32, f32),
+    /// 伤害倍数
+    pub multiplier: u32,
+}
+
+/// Boss 的阶段
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BossPhaseConfig {
+    /// 进入该阶段的生命值比例
+    pub health: f32,
+    /// 移动路径
+    pub path: FormationKind,
+    /// 攻击方式
+    pub pattern: FirePattern,
+    /// 平均每秒攻击次数
+    pub fire_rate: f64,
+}
+
+/// 游戏模式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameMode {
+    /// 敌人按阵型沿路径飞行
+    #[default]
+    Waves,
+    /// 经典的网格行进
+    Grid,
+}
+
+impl FromStr for GameMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "waves" => Ok(GameMode::Waves),
+            "grid" => Ok(GameMode::Grid),
+            other => Err(format!(
+                "unknown game mode `{other}`, expected `waves` or `grid`"
+            )),
+        }
+    }
+}
+
+/// 网格模式配置
+///
+/// 整个网格同步左右移动，到达边缘时下移并加快节奏，每列最下方的敌人负责攻击
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GridConfig {
+    /// 列数
+    pub columns: u32,
+    /// 每行的敌人类型，从上到下
+    pub rows: Vec<EnemyKind>,
+    /// 相邻敌人中心的间距 (x, y)
+    pub spacing: (f32, f32),
+    /// 最上面一行距窗口顶部的距离
+    pub top: f32,
+    /// 每拍横向移动的距离
+    pub step: f32,
+    /// 第一波每拍的间隔 (秒)，后面的波次按敌人速度加快
+    pub beat_seconds: f32,
+    /// 到达边缘时下移的距离
+    pub drop: f32,
+    /// 每次下移后每拍间隔的倍数
+    pub speed_up: f32,
+}
+
+/// 掩体配置
+///
+/// 掩体由格子组成，被任意一方的激光击中或被敌人穿过时格子被逐个摧毁
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BunkersConfig {
+    /// 掩体数量，为 0 时不生成
+    pub count: u32,
+    /// 掩体形状，从上到下每行一个字符串，`#` 为格子，其他字符为空
+    pub shape: Vec<String>,
+    /// 格子大小
+    pub cell_size: f32,
+    /// 掩体中心距窗口底部的距离
+    pub height: f32,
+    /// 格子颜色 (r, g, b)
+    pub color: (f32, f32, f32),
+    /// 被击中时波及周围格子的半径，范围内的格子有一半的概率被摧毁
+    pub blast_radius: f32,
+}
+
+/// 蓄力射击配置
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChargeShotConfig {
+    /// 是否启用，启用后按住攻击键蓄力、松开时发射，不再自动连发
+    pub enabled: bool,
+    /// 按住超过该时间 (秒) 后松开发射蓄力弹道，否则发射普通弹道
+    pub min_seconds: f32,
+    /// 蓄满所需时间 (秒)
+    pub full_seconds: f32,
+    /// 蓄满时的伤害倍数
+    pub damage_scale: f32,
+    /// 蓄满时的弹道大小倍数
+    pub size_scale: f32,
+}
+
+/// 各类敌人的属性配置
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnemiesConfig {
+    /// 侦察机
+    pub scout: EnemyKindConfig,
+    /// 炮艇
+    pub gunner: EnemyKindConfig,
+    /// 坦克
+    pub tank: EnemyKindConfig,
+    /// 自杀机
+    pub kamikaze: EnemyKindConfig,
+}
+
+/// 单类敌人的属性配置
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnemyKindConfig {
+    /// 图片
+    pub sprite: SpriteConfig,
+    /// 是否上下翻转图片
+    #[serde(default)]
+    pub flip_y: bool,
+    /// 图片颜色 (r, g, b)
+    pub tint: (f32, f32, f32),
+    /// 相对 `sprite_scale` 的缩放比例
+    pub scale: f32,
+    /// 生命值
+    pub hit_points: u32,
+    /// 被消灭时的得分
+    pub score: u32,
+    /// 相对波次速度的倍数
+    pub speed_scale: f32,
+    /// 相对波次攻击频率的倍数
+    pub fire_rate_scale: f64,
+    /// 武器，为空时不攻击
+    #[serde(default)]
+    pub weapon: Option<WeaponKind>,
+    /// 攻击方式
+    #[serde(default)]
+    pub pattern: FirePattern,
+    /// 与玩家机体相撞时对玩家造成的伤害
+    pub contact_damage: u32,
+    /// 是否在接近玩家时脱离阵型撞向玩家
+    #[serde(default)]
+    pub ram: bool,
+}
+
+/// 机体碰撞配置
+///
+/// 玩家与敌人的机体相撞时双方都受到伤害，敌人受到的伤害为 `player_damage`，
+/// 玩家受到的伤害为敌人的 `contact_damage`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CollisionConfig {
+    /// 玩家机体撞击敌人时造成的伤害
+    pub player_damage: u32,
+    /// 同一个敌人两次撞击之间的间隔 (秒)
+    pub contact_cooldown: f32,
+    /// 撞击型敌人与玩家的距离小于该值时脱离阵型
+    pub ram_range: f32,
+    /// 撞击时相对阵型速度的倍数
+    pub ram_speed_scale: f32,
+    /// 撞击时每秒最多转向的角度 (度)
+    pub ram_turn_rate: f32,
+}
+
+/// 道具配置
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PowerUpsConfig {
+    /// 敌人被消灭时掉落道具的概率
+    pub drop_chance: f64,
+    /// 掉落表：道具类型及权重
+    pub drop_table: Vec<(PowerUpKind, u32)>,
+    /// 下落速度，相对基础速度的倍数
+    pub fall_speed: f32,
+    /// 道具大小
+    pub size: (f32, f32),
+    /// 限时效果持续时间 (秒)
+    pub duration: f32,
+    /// 得分倍数
+    pub score_multiplier: u32,
+    /// 连射间隔 (秒)
+    pub rapid_fire_interval: f64,
+}
+
+/// 手柄配置
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GamepadConfig {
+    /// 左摇杆死区，推动幅度小于该值时不移动
+    pub dead_zone: f32,
+}
+
+/// 声音资源配置
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AudiosConfig {
+    /// 敌人被摧毁声音
+    pub enemy_explosion: String,
+    /// 玩家被摧毁的声音
+    pub player_explosion: String,
+    /// 玩家发射激光的声音
+    pub player_laser: String,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            mode: GameMode::Waves,
+            window_size: WIN_SIZE,
+            time_step: TIME_STEP,
+            base_speed: BASE_SPEED,
+            sprite_scale: SPRITE_SCALE,
+            max_enemy: MAX_ENEMY,
+            formation_member_max: FORMATION_MEMBER_MAX,
+            enemy_fire_rate: ENEMY_FIRE_RATE,
+            player_lives: PLAYER_LIVES,
+            player_hit_points: PLAYER_HIT_POINTS,
+            player_respawn_delay: PLAYER_RESPAWN_DELAY,
+            wave_banner_seconds: WAVE_BANNER_SECONDS,
+            sprites: SpritesConfig::default(),
+            weapons: WeaponsConfig::default(),
+            charge_shot: ChargeShotConfig::default(),
+            enemies: EnemiesConfig::default(),
+            boss: BossConfig::default(),
+            collision: CollisionConfig::default(),
+            grid: GridConfig::default(),
+            bunkers: BunkersConfig::default(),
+            power_ups: PowerUpsConfig::default(),
+            gamepad: GamepadConfig::default(),
+            audios: AudiosConfig::default(),
+            font: KENNEY_BLOCK_FONT.to_owned(),
+        }
+    }
+}
+
+impl Default for SpritesConfig {
+    fn default() -> Self {
+        Self {
+            background: BACKGROUND_SPRITE.to_owned(),
+            player: SpriteConfig::new(PLAYER_SPRITE, PLAYER_SIZE).with_shape(ShapeConfig::Mask),
+            explosion: ExplosionConfig {
+                path: EXPLOSION_SHEET.to_owned(),
+                size: EXPLOSION_SIZE,
+                columns: 4,
+                rows: 4,
+                frames: EXPLOSION_ANIMATION_LEN,
+            },
+        }
+    }
+}
+
+impl Default for WeaponsConfig {
+    fn default() -> Self {
+        let player_laser =
+            SpriteConfig::new(PLAYER_LASER_SPRITE, PLAYER_LASER_SIZE).with_shape(ShapeConfig::Obb);
+        let enemy_laser =
+            SpriteConfig::new(ENEMY_LASER_SPRITE, ENEMY_LASER_SIZE).with_shape(ShapeConfig::Obb);
+        Self {
+            twin: Weapon {
+                sprite: player_laser.clone(),
+                count: 2,
+                spacing: 62.,
+                spread: 0.,
+                speed: 1.,
+                damage: 1,
+                cooldown: 0.25,
+                pierce: 0,
+                homing: 0.,
+            },
+            spread: Weapon {
+                sprite: player_laser.clone(),
+                count: 5,
+                spacing: 0.,
+                spread: 40.,
+                speed: 1.,
+                damage: 1,
+                cooldown: 0.35,
+                pierce: 0,
+                homing: 0.,
+            },
+            beam: Weapon {
+                sprite: SpriteConfig::new(PLAYER_LASER_SPRITE, (9., 160.))
+                    .with_shape(ShapeConfig::Obb),
+                count: 1,
+                spacing: 0.,
+                spread: 0.,
+                speed: 2.,
+                damage: 2,
+                cooldown: 0.5,
+                pierce: 3,
+                homing: 0.,
+            },
+            homing: Weapon {
+                sprite: player_laser,
+                count: 2,
+                spacing: 40.,
+                spread: 30.,
+                speed: 0.7,
+                damage: 1,
+                cooldown: 0.6,
+                pierce: 0,
+                homing: 180.,
+            },
+            enemy_single: Weapon {
+                sprite: enemy_laser.clone(),
+                count: 1,
+                spacing: 0.,
+                spread: 0.,
+                speed: 1.,
+                damage: 1,
+                cooldown: 0.5,
+                pierce: 0,
+                homing: 0.,
+            },
+            enemy_twin: Weapon {
+                sprite: enemy_laser,
+                count: 2,
+                spacing: 36.,
+                spread: 0.,
+                speed: 1.,
+                damage: 1,
+                cooldown: 0.5,
+                pierce: 0,
+                homing: 0.,
+            },
+        }
+    }
+}
+
+impl WeaponsConfig {
+    /// 获取指定类型武器的属性
+    pub fn get(&self, kind: WeaponKind) -> &Weapon {
+        match kind {
+            WeaponKind::Twin => &self.twin,
+            WeaponKind::Spread => &self.spread,
+            WeaponKind::Beam => &self.beam,
+            WeaponKind::Homing => &self.homing,
+            WeaponKind::EnemySingle => &self.enemy_single,
+            WeaponKind::EnemyTwin => &self.enemy_twin,
+        }
+    }
+}
+
+impl Default for BossConfig {
+    fn default() -> Self {
+        Self {
+            waves: vec![3],
+            every: 5,
+            sprite: SpriteConfig::new(ENEMY_SPRITE, ENEMY_SIZE),
+            tint: (0.8, 0.5, 1.),
+            scale: 2.,
+            hit_points: 60,
+            score: 50,
+            speed_scale: 0.5,
+            contact_damage: 3,
+            weapon: WeaponKind::EnemySingle,
+            zones: vec![
+                // 核心，伤害加倍
+                HitZoneConfig {
+                    offset: (0., 0.),
+                    size: (40., 40.),
+                    multiplier: 2,
+                },
+                // 左右两翼
+                HitZoneConfig {
+                    offset: (-50., 5.),
+                    size: (44., 50.),
+                    multiplier: 1,
+                },
+                HitZoneConfig {
+                    offset: (50., 5.),
+                    size: (44., 50.),
+                    multiplier: 1,
+                },
+            ],
+            phases: vec![
+                BossPhaseConfig {
+                    health: 1.,
+                    path: FormationKind::Wide,
+                    pattern: FirePattern::Volley {
+                        shots: 3,
+                        interval: 0.12,
+                    },
+                    fire_rate: 0.8,
+                },
+                BossPhaseConfig {
+                    health: 0.6,
+                    path: FormationKind::FigureEight,
+                    pattern: FirePattern::Spiral { arms: 4, step: 15. },
+                    fire_rate: 3.,
+                },
+                BossPhaseConfig {
+                    health: 0.3,
+                    path: FormationKind::DiveBomb,
+                    pattern: FirePattern::Radial { count: 12 },
+                    fire_rate: 0.8,
+                },
+            ],
+        }
+    }
+}
+
+impl BossConfig {
+    /// 第 `wave` 波的末尾是否出现 Boss
+    pub fn appears_in(&self, wave: u32) -> bool {
+        self.waves.contains(&wave) || (self.every > 0 && wave % self.every == 0)
+    }
+
+    /// 生命值比例为 `health` 时所处的阶段
+    pub fn phase(&self, health: f32) -> usize {
+        self.phases
+            .iter()
+            .rposition(|phase| health <= phase.health)
+            .unwrap_or(0)
+    }
+}
+
+impl Default for CollisionConfig {
+    fn default() -> Self {
+        Self {
+            player_damage: 2,
+            contact_cooldown: 0.5,
+            ram_range: 350.,
+            ram_speed_scale: 1.5,
+            ram_turn_rate: 90.,
+        }
+    }
+}
+
+impl Default for GamepadConfig {
+    fn default() -> Self {
+        Self { dead_zone: 0.2 }
+    }
+}
+
+impl Default for GridConfig {
+    fn default() -> Self {
+        Self {
+            columns: 8,
+            rows: vec![
+                EnemyKind::Tank,
+                EnemyKind::Gunner,
+                EnemyKind::Gunner,
+                EnemyKind::Scout,
+                EnemyKind::Scout,
+            ],
+            spacing: (62., 45.),
+            top: 90.,
+            step: 10.,
+            beat_seconds: 0.5,
+            drop: 20.,
+            speed_up: 0.9,
+        }
+    }
+}
+
+impl Default for BunkersConfig {
+    fn default() -> Self {
+        let shape = [
+            "    ############    ",
+            "  ################  ",
+            " ################## ",
+            "####################",
+            "####################",
+            "####################",
+            "######        ######",
+            "#####          #####",
+        ];
+        Self {
+            count: 4,
+            shape: shape.into_iter().map(str::to_owned).collect(),
+            cell_size: 5.,
+            height: 130.,
+            color: (0.3, 0.9, 0.3),
+            blast_radius: 8.,
+        }
+    }
+}
+
+impl Default for ChargeShotConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_seconds: CHARGE_MIN_SECONDS,
+            full_seconds: CHARGE_FULL_SECONDS,
+            damage_scale: 3.,
+            size_scale: 2.,
+        }
+    }
+}
+
+impl ChargeShotConfig {
+    /// 按住攻击键 `seconds` 秒后的蓄力程度 (0 到 1)，未达到最短蓄力时间时返回 `None`
+    pub fn level(&self, seconds: f32) -> Option<f32> {
+        if seconds < self.min_seconds {
+            return None;
+        }
+        let level = (seconds - self.min_seconds) / (self.full_seconds - self.min_seconds);
+        Some(level.clamp(0., 1.))
+    }
+}
+
+impl Default for EnemiesConfig {
+    fn default() -> Self {
+        Self {
+            scout: EnemyKindConfig {
+                sprite: SpriteConfig::new(ENEMY_SPRITE, ENEMY_SIZE).with_shape(ShapeConfig::Mask),
+                flip_y: false,
+                tint: (1., 1., 1.),
+                scale: 0.8,
+                hit_points: 1,
+                score: 1,
+                speed_scale: 1.2,
+                fire_rate_scale: 1.,
+                weapon: Some(WeaponKind::EnemySingle),
+                pattern: FirePattern::Aimed,
+                contact_damage: 1,
+                ram: false,
+            },
+            gunner: EnemyKindConfig {
+                sprite: SpriteConfig::new(ENEMY_SPRITE, ENEMY_SIZE).with_shape(ShapeConfig::Mask),
+                flip_y: false,
+                tint: (1., 0.6, 0.6),
+                scale: 1.,
+                hit_points: 2,
+                score: 3,
+                speed_scale: 1.,
+                fire_rate_scale: 1.,
+                weapon: Some(WeaponKind::EnemyTwin),
+                pattern: FirePattern::Volley {
+                    shots: 3,
+                    interval: 0.15,
+                },
+                contact_damage: 1,
+                ram: false,
+            },
+            tank: EnemyKindConfig {
+                sprite: SpriteConfig::new(TANK_SPRITE, TANK_SIZE).with_shape(ShapeConfig::Mask),
+                flip_y: true,
+                tint: (0.7, 0.9, 1.),
+                scale: 1.2,
+                hit_points: 5,
+                score: 5,
+                speed_scale: 0.7,
+                fire_rate_scale: 0.4,
+                weapon: Some(WeaponKind::EnemySingle),
+                pattern: FirePattern::Radial { count: 8 },
+                contact_damage: 2,
+                ram: false,
+            },
+            kamikaze: EnemyKindConfig {
+                sprite: SpriteConfig::new(ENEMY_SPRITE, ENEMY_SIZE).with_shape(ShapeConfig::Mask),
+                flip_y: false,
+                tint: (1., 1., 0.4),
+                scale: 0.7,
+                hit_points: 1,
+                score: 2,
+                speed_scale: 1.6,
+                fire_rate_scale: 0.,
+                weapon: None,
+                pattern: FirePattern::Straight,
+                contact_damage: 2,
+                ram: true,
+            },
+        }
+    }
+}
+
+impl EnemiesConfig {
+    /// 获取指定类型敌人的属性
+    pub fn get(&self, kind: EnemyKind) -> &EnemyKindConfig {
+        match kind {
+            EnemyKind::Scout => &self.scout,
+            EnemyKind::Gunner => &self.gunner,
+            EnemyKind::Tank => &self.tank,
+            EnemyKind::Kamikaze => &self.kamikaze,
+        }
+    }
+}
+
+impl Default for PowerUpsConfig {
+    fn default() -> Self {
+        Self {
+            drop_chance: POWER_UP_DROP_CHANCE,
+            drop_table: vec![
+                (PowerUpKind::SpreadShot, 3),
+                (PowerUpKind::RapidFire, 3),
+                (PowerUpKind::Shield, 2),
+                (PowerUpKind::ExtraLife, 1),
+                (PowerUpKind::ScoreMultiplier, 2),
+            ],
+            fall_speed: 0.3,
+            size: POWER_UP_SIZE,
+            duration: POWER_UP_SECONDS,
+            score_multiplier: 2,
+            rapid_fire_interval: 0.15,
+        }
+    }
+}
+
+impl Default for AudiosConfig {
+    fn default() -> Self {
+        Self {
+            enemy_explosion: ENEMY_EXPLOSION_AUDIO.to_owned(),
+            player_explosion: PLAYER_EXPLOSION_AUDIO.to_owned(),
+            player_laser: PLAYER_LASER_AUDIO.to_owned(),
+        }
+    }
+}
+
+impl SpriteConfig {
+    pub fn new(path: &str, size: (f32, f32)) -> Self {
+        Self {
+            path: path.to_owned(),
+            size,
+            shape: ShapeConfig::Aabb,
+        }
+    }
+
+    /// 使用指定的碰撞形状
+    pub fn with_shape(mut self, shape: ShapeConfig) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// 按比例缩放的图片，圆形碰撞体的半径一同缩放
+    pub fn scaled(&self, scale: f32) -> Self {
+        let (w, h) = self.size;
+        let shape = match self.shape {
+            ShapeConfig::Circle(radius) => ShapeConfig::Circle(radius * scale),
+            shape => shape,
+        };
+        Self {
+            path: self.path.clone(),
+            size: (w * scale, h * scale),
+            shape,
+        }
+    }
+}
+
+/// 配置错误
+#[derive(Debug)]
+pub enum ConfigError {
+    /// 无法读取文件
+    Io(PathBuf, io::Error),
+    /// 无法解析文件
+    Parse(PathBuf, ron::error::SpannedError),
+    /// 字段取值不合法
+    Invalid {
+        /// 字段名，嵌套字段以 `.` 连接
+        key: String,
+        /// 原因
+        reason: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, err) => write!(f, "failed to read {}: {err}", path.display()),
+            ConfigError::Parse(path, err) => write!(f, "failed to parse {}: {err}", path.display()),
+            ConfigError::Invalid { key, reason } => write!(f, "invalid config `{key}`: {reason}"),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::Io(_, err) => Some(err),
+            ConfigError::Parse(_, err) => Some(err),
+            ConfigError::Invalid { .. } => None,
+        }
+    }
+}
+
+impl GameConfig {
+    /// 读取并校验配置文件
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let content =
+            fs::read_to_string(path).map_err(|err| ConfigError::Io(path.to_owned(), err))?;
+        let config: Self =
+            ron::from_str(&content).map_err(|err| ConfigError::Parse(path.to_owned(), err))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// 默认配置文件存在时读取，否则使用默认值
+    pub fn load_default() -> Result<Self, ConfigError> {
+        if Path::new(DEFAULT_CONFIG_PATH).exists() {
+            Self::load(DEFAULT_CONFIG_PATH)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// 校验字段取值，返回第一个不合法的字段
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check_size("window_size", self.window_size)?;
+        check(
+            "time_step",
+            self.time_step > 0. && self.time_step <= 0.25,
+            "must be in (0, 0.25] seconds",
+        )?;
+        check_positive("base_speed", self.base_speed)?;
+        check_positive("sprite_scale", self.sprite_scale)?;
+        check("max_enemy", self.max_enemy > 0, "must be at least 1")?;
+        check(
+            "formation_member_max",
+            self.formation_member_max > 0,
+            "must be at least 1",
+        )?;
+        check(
+            "enemy_fire_rate",
+            self.enemy_fire_rate.is_finite() && self.enemy_fire_rate >= 0.,
+            "must be a non-negative number",
+        )?;
+        check("player_lives", self.player_lives > 0, "must be at least 1")?;
+        check(
+            "player_hit_points",
+            self.player_hit_points > 0,
+            "must be at least 1",
+        )?;
+        check(
+            "player_respawn_delay",
+            self.player_respawn_delay.is_finite() && self.player_respawn_delay >= 0.,
+            "must be a non-negative number",
+        )?;
+        check(
+            "wave_banner_seconds",
+            self.wave_banner_seconds.is_finite() && self.wave_banner_seconds >= 0.,
+            "must be a non-negative number",
+        )?;
+
+        let sprites = &self.sprites;
+        check_path("sprites.background", &sprites.background)?;
+        check_sprite("sprites.player", &sprites.player)?;
+
+        let explosion = &sprites.explosion;
+        check_path("sprites.explosion.path", &explosion.path)?;
+        check_size("sprites.explosion.size", explosion.size)?;
+        check(
+            "sprites.explosion.columns",
+            explosion.columns > 0,
+            "must be at least 1",
+        )?;
+        check(
+            "sprites.explosion.rows",
+            explosion.rows > 0,
+            "must be at least 1",
+        )?;
+        check(
+            "sprites.explosion.frames",
+            explosion.frames > 0 && explosion.frames <= explosion.columns * explosion.rows,
+            "must be between 1 and columns * rows",
+        )?;
+
+        for kind in WeaponKind::ALL {
+            let key = format!("weapons.{}", kind.config_key());
+            let weapon = self.weapons.get(kind);
+            check_sprite(&format!("{key}.sprite"), &weapon.sprite)?;
+            // 弹道图片按大小拉伸，不使用像素遮罩
+            check(
+                &format!("{key}.sprite.shape"),
+                weapon.sprite.shape != ShapeConfig::Mask,
+                "laser sprites do not support pixel masks",
+            )?;
+            check(
+                &format!("{key}.count"),
+                weapon.count > 0,
+                "must be at least 1",
+            )?;
+            check(
+                &format!("{key}.spacing"),
+                weapon.spacing.is_finite() && weapon.spacing >= 0.,
+                "must be a non-negative number",
+            )?;
+            check(
+                &format!("{key}.spread"),
+                (0. ..=360.).contains(&weapon.spread),
+                "must be between 0 and 360 degrees",
+            )?;
+            check_positive(&format!("{key}.speed"), weapon.speed)?;
+            check(
+                &format!("{key}.damage"),
+                weapon.damage > 0,
+                "must be at least 1",
+            )?;
+            check(
+                &format!("{key}.cooldown"),
+                weapon.cooldown.is_finite() && weapon.cooldown >= 0.,
+                "must be a non-negative number",
+            )?;
+            check(
+                &format!("{key}.homing"),
+                weapon.homing.is_finite() && weapon.homing >= 0.,
+                "must be a non-negative number",
+            )?;
+        }
+
+        let charge_shot = &self.charge_shot;
+        check(
+            "charge_shot.min_seconds",
+            charge_shot.min_seconds.is_finite() && charge_shot.min_seconds >= 0.,
+            "must be a non-negative number",
+        )?;
+        check(
+            "charge_shot.full_seconds",
+            charge_shot.full_seconds.is_finite()
+                && charge_shot.full_seconds > charge_shot.min_seconds,
+            "must be greater than min_seconds",
+        )?;
+        check(
+            "charge_shot.damage_scale",
+            charge_shot.damage_scale.is_finite() && charge_shot.damage_scale >= 1.,
+            "must be at least 1",
+        )?;
+        check(
+            "charge_shot.size_scale",
+            charge_shot.size_scale.is_finite() && charge_shot.size_scale >= 1.,
+            "must be at least 1",
+        )?;
+
+        for kind in EnemyKind::ALL {
+            let key = format!("enemies.{}", kind.config_key());
+            let enemy = self.enemies.get(kind);
+            check_sprite(&format!("{key}.sprite"), &enemy.sprite)?;
+            let (r, g, b) = enemy.tint;
+            check(
+                &format!("{key}.tint"),
+                [r, g, b].iter().all(|c| (0. ..=1.).contains(c)),
+                "color components must be between 0 and 1",
+            )?;
+            check_positive(&format!("{key}.scale"), enemy.scale)?;
+            check(
+                &format!("{key}.hit_points"),
+                enemy.hit_points > 0,
+                "must be at least 1",
+            )?;
+            check_positive(&format!("{key}.speed_scale"), enemy.speed_scale)?;
+            check(
+                &format!("{key}.fire_rate_scale"),
+                enemy.fire_rate_scale.is_finite() && enemy.fire_rate_scale >= 0.,
+                "must be a non-negative number",
+            )?;
+            check_pattern(&format!("{key}.pattern"), enemy.pattern)?;
+        }
+
+        let boss = &self.boss;
+        check_sprite("boss.sprite", &boss.sprite)?;
+        let (r, g, b) = boss.tint;
+        check(
+            "boss.tint",
+            [r, g, b].iter().all(|c| (0. ..=1.).contains(c)),
+            "color components must be between 0 and 1",
+        )?;
+        check_positive("boss.scale", boss.scale)?;
+        check("boss.hit_points", boss.hit_points > 0, "must be at least 1")?;
+        check_positive("boss.speed_scale", boss.speed_scale)?;
+        check(
+            "boss.zones",
+            !boss.zones.is_empty(),
+            "must contain at least one zone",
+        )?;
+        for (i, zone) in boss.zones.iter().enumerate() {
+            check_size(&format!("boss.zones[{i}].size"), zone.size)?;
+            check(
+                &format!("boss.zones[{i}].multiplier"),
+                zone.multiplier > 0,
+                "must be at least 1",
+            )?;
+        }
+        check(
+            "boss.phases",
+            boss.phases.first().is_some_and(|phase| phase.health == 1.),
+            "must start with a phase at health 1.0",
+        )?;
+        for (i, phase) in boss.phases.iter().enumerate() {
+            let key = format!("boss.phases[{i}]");
+            let previous = if i == 0 {
+                1.
+            } else {
+                boss.phases[i - 1].health
+            };
+            check(
+                &format!("{key}.health"),
+                phase.health > 0. && (i == 0 || phase.health < previous),
+                "must be in (0, 1] and lower than the previous phase",
+            )?;
+            check_pattern(&format!("{key}.pattern"), phase.pattern)?;
+            check(
+                &format!("{key}.fire_rate"),
+                phase.fire_rate.is_finite() && phase.fire_rate > 0.,
+                "must be a positive number",
+            )?;
+        }
+
+        let collision = &self.collision;
+        check_positive("collision.contact_cooldown", collision.contact_cooldown)?;
+        check(
+            "collision.ram_range",
+            collision.ram_range.is_finite() && collision.ram_range >= 0.,
+            "must be a non-negative number",
+        )?;
+        check_positive("collision.ram_speed_scale", collision.ram_speed_scale)?;
+        check(
+            "collision.ram_turn_rate",
+            collision.ram_turn_rate.is_finite() && collision.ram_turn_rate >= 0.,
+            "must be a non-negative number",
+        )?;
+
+        let grid = &self.grid;
+        check("grid.columns", grid.columns > 0, "must be at least 1")?;
+        check(
+            "grid.rows",
+            !grid.rows.is_empty(),
+            "must contain at least one row",
+        )?;
+        check_size("grid.spacing", grid.spacing)?;
+        check(
+            "grid.top",
+            grid.top.is_finite() && grid.top >= 0.,
+            "must be a non-negative number",
+        )?;
+        check_positive("grid.step", grid.step)?;
+        check_positive("grid.beat_seconds", grid.beat_seconds)?;
+        check(
+            "grid.drop",
+            grid.drop.is_finite() && grid.drop >= 0.,
+            "must be a non-negative number",
+        )?;
+        check(
+            "grid.speed_up",
+            grid.speed_up > 0. && grid.speed_up <= 1.,
+            "must be in (0, 1]",
+        )?;
+
+        let bunkers = &self.bunkers;
+        check(
+            "bunkers.shape",
+            bunkers.count == 0 || bunkers.shape.iter().any(|row| row.contains('#')),
+            "must contain at least one `#` cell",
+        )?;
+        check_positive("bunkers.cell_size", bunkers.cell_size)?;
+        check(
+            "bunkers.height",
+            bunkers.height.is_finite() && bunkers.height >= 0.,
+            "must be a non-negative number",
+        )?;
+        let (r, g, b) = bunkers.color;
+        check(
+            "bunkers.color",
+            [r, g, b].iter().all(|c| (0. ..=1.).contains(c)),
+            "color components must be between 0 and 1",
+        )?;
+        check(
+            "bunkers.blast_radius",
+            bunkers.blast_radius.is_finite() && bunkers.blast_radius >= 0.,
+            "must be a non-negative number",
+        )?;
+
+        let power_ups = &self.power_ups;
+        check(
+            "power_ups.drop_chance",
+            (0. ..=1.).contains(&power_ups.drop_chance),
+            "must be between 0 and 1",
+        )?;
+        check(
+            "power_ups.drop_table",
+            power_ups.drop_chance == 0. || power_ups.drop_table.iter().any(|(_, w)| *w > 0),
+            "must contain at least one entry with a positive weight",
+        )?;
+        check_positive("power_ups.fall_speed", power_ups.fall_speed)?;
+        check_size("power_ups.size", power_ups.size)?;
+        check_positive("power_ups.duration", power_ups.duration)?;
+        check(
+            "power_ups.score_multiplier",
+            power_ups.score_multiplier > 0,
+            "must be at least 1",
+        )?;
+        check(
+            "power_ups.rapid_fire_interval",
+            power_ups.rapid_fire_interval.is_finite() && power_ups.rapid_fire_interval > 0.,
+            "must be a positive number",
+        )?;
+
+        check(
+            "gamepad.dead_zone",
+            (0. ..1.).contains(&self.gamepad.dead_zone),
+            "must be in [0, 1)",
+        )?;
+
+        check_path("audios.enemy_explosion", &self.audios.enemy_explosion)?;
+        check_path("audios.player_explosion", &self.audios.player_explosion)?;
+        check_path("audios.player_laser", &self.audios.player_laser)?;
+        check_path("font", &self.font)
+    }
+}
+
+fn check(key: &str, valid: bool, reason: &str) -> Result<(), ConfigError> {
+    if valid {
+        Ok(())
+    } else {
+        Err(ConfigError::Invalid {
+            key: key.to_owned(),
+            reason: reason.to_owned(),
+        })
+    }
+}
+
+fn check_pattern(key: &str, pattern: FirePattern) -> Result<(), ConfigError> {
+    match pattern {
+        FirePattern::Straight | FirePattern::Aimed => Ok(()),
+        FirePattern::Radial { count } => {
+            check(&format!("{key}.count"), count > 0, "must be at least 1")
+        }
+        FirePattern::Spiral { arms, step } => {
+            check(&format!("{key}.arms"), arms > 0, "must be at least 1")?;
+            check(&format!("{key}.step"), step.is_finite(), "must be a number")
+        }
+        FirePattern::Volley { shots, interval } => {
+            check(&format!("{key}.shots"), shots > 0, "must be at least 1")?;
+            check_positive(&format!("{key}.interval"), interval)
+        }
+    }
+}
+
+fn check_positive(key: &str, value: f32) -> Result<(), ConfigError> {
+    check(
+        key,
+        value.is_finite() && value > 0.,
+        "must be a positive number",
+    )
+}
+
+fn check_size(key: &str, (w, h): (f32, f32)) -> Result<(), ConfigError> {
+    check(
+        key,
+        w.is_finite() && h.is_finite() && w > 0. && h > 0.,
+        "width and height must be positive numbers",
+    )
+}
+
+fn check_sprite(key: &str, sprite: &SpriteConfig) -> Result<(), ConfigError> {
+    check_path(&format!("{key}.path"), &sprite.path)?;
+    check_size(&format!("{key}.size"), sprite.size)?;
+    match sprite.shape {
+        ShapeConfig::Circle(radius) => check_positive(&format!("{key}.shape"), radius),
+        ShapeConfig::Aabb | ShapeConfig::Obb | ShapeConfig::Mask => Ok(()),
+    }
+}
+
+fn check_path(key: &str, path: &str) -> Result<(), ConfigError> {
+    check(key, !path.trim().is_empty(), "asset path must not be empty")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 校验失败时返回的字段名
+    fn invalid_key(config: &GameConfig) -> String {
+        match config.validate() {
+            Err(ConfigError::Invalid { key, .. }) => key,
+            other => panic!("expected an invalid config, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn default_config_is_valid() {
+        GameConfig::default().validate().unwrap();
+    }
+
+    #[test]
+    fn invalid_values_report_their_key() {
+        let mut config = GameConfig::default();
+        config.gamepad.dead_zone = 1.;
+        assert_eq!(invalid_key(&config), "gamepad.dead_zone");
+
+        let mut config = GameConfig::default();
+        config.grid.rows.clear();
+        assert_eq!(invalid_key(&config), "grid.rows");
+
+        let mut config = GameConfig::default();
+        let explosion = &mut config.sprites.explosion;
+        explosion.frames = explosion.columns * explosion.rows + 1;
+        assert_eq!(invalid_key(&config), "sprites.explosion.frames");
+
+        let mut config = GameConfig::default();
+        config.sprites.explosion.frames = 0;
+        assert_eq!(invalid_key(&config), "sprites.explosion.frames");
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        assert!(ron::from_str::<GameConfig>("(time_step: 0.02)").is_ok());
+        assert!(ron::from_str::<GameConfig>("(time_stp: 0.02)").is_err());
+        assert!(ron::from_str::<GameConfig>("(grid: (colums: 3))").is_err());
+    }
+}
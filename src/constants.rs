@@ -1,3 +1,8 @@
+use bevy::prelude::Color;
+
+/// 游戏区域大小，同时作为窗口的初始大小
+pub const WIN_SIZE: (f32, f32) = (598., 676.);
+
 /// 游戏背景图片路径
 pub const BACKGROUND_SPRITE: &str = "images/planet05.png";
 
@@ -14,6 +19,10 @@ pub const PLAYER_LASER_SIZE: (f32, f32) = (9., 54.);
 pub const ENEMY_SPRITE: &str = "images/enemy_a_01.png";
 /// 敌人大小
 pub const ENEMY_SIZE: (f32, f32) = (144., 75.);
+/// 坦克敌人图片路径
+pub const TANK_SPRITE: &str = "images/player_b_01.png";
+/// 坦克敌人大小
+pub const TANK_SIZE: (f32, f32) = (98., 75.);
 /// 敌人攻击图片路径
 pub const ENEMY_LASER_SPRITE: &str = "images/laser_b_01.png";
 /// 敌人攻击图片大小
@@ -29,15 +38,45 @@ pub const EXPLOSION_ANIMATION_LEN: usize = 16;
 /// 图片缩放比例
 pub const SPRITE_SCALE: f32 = 0.5;
 
-/// 步长 (帧数)
+/// 默认固定步长 (秒)
 pub const TIME_STEP: f32 = 1. / 60.;
 /// 基础速度
 pub const BASE_SPEED: f32 = 500.;
-/// 敌人最大数量
+/// 第一波敌人平均每秒攻击次数
+pub const ENEMY_FIRE_RATE: f64 = 1.;
+/// 第一波同时存在的敌人最大数量
 pub const MAX_ENEMY: u32 = 2;
+/// 玩家初始生命数
+pub const PLAYER_LIVES: u32 = 3;
+/// 玩家护盾值，每条生命的护盾耗尽时被摧毁
+pub const PLAYER_HIT_POINTS: u32 = 3;
+/// 受击闪烁时间 (秒)
+pub const HIT_FLASH_SECONDS: f32 = 0.1;
+/// 受击闪烁颜色
+pub const HIT_FLASH_COLOR: Color = Color::rgba(1., 1., 1., 0.35);
+/// 护盾条大小
+pub const SHIELD_BAR_SIZE: (f32, f32) = (120., 8.);
+/// Boss 生命条大小
+pub const BOSS_BAR_SIZE: (f32, f32) = (300., 10.);
+/// 空间哈希的格子大小
+pub const SPATIAL_HASH_CELL_SIZE: f32 = 64.;
+/// 像素遮罩中视为不透明的最小透明度
+pub const MASK_ALPHA_THRESHOLD: u8 = 128;
+/// 敌人被消灭时掉落道具的概率
+pub const POWER_UP_DROP_CHANCE: f64 = 0.15;
+/// 道具大小
+pub const POWER_UP_SIZE: (f32, f32) = (24., 24.);
+/// 限时道具持续时间 (秒)
+pub const POWER_UP_SECONDS: f32 = 10.;
+/// 蓄力射击的最短蓄力时间 (秒)
+pub const CHARGE_MIN_SECONDS: f32 = 0.3;
+/// 蓄力射击蓄满所需时间 (秒)
+pub const CHARGE_FULL_SECONDS: f32 = 1.2;
 /// 玩家自动重生时间
 pub const PLAYER_RESPAWN_DELAY: f64 = 2.;
-/// 阵型内敌人最大数量
+/// 波次横幅展示时间 (秒)
+pub const WAVE_BANNER_SECONDS: f32 = 2.;
+/// 第一波阵型内敌人最大数量
 pub const FORMATION_MEMBER_MAX: u32 = 2;
 
 /// 敌人被摧毁声音
@@ -0,0 +1,371 @@
+use bevy::{math::Vec3Swizzles, prelude::*, sprite::Anchor, utils::HashSet};
+use rand::Rng;
+
+use super::{
+    formation::{Formation, FormationPath},
+    pattern::EnemyGun,
+    wave::WaveManager,
+};
+use crate::{
+    collision::Collider,
+    components::{Enemy, ExplosionToSpawn, Health, HitFlash, HudAnchor, SpriteSize},
+    config::{BossConfig, GameConfig},
+    constants::{BOSS_BAR_SIZE, HIT_FLASH_COLOR},
+    events::{DamageEvent, EnemyKilledEvent},
+    resource::GameRng,
+    weapon::Weapon,
+    GameSet, GameTextures, MaxEnemy, Playfield,
+};
+
+/// Boss 组件，记录当前所处的阶段
+#[derive(Component)]
+pub struct Boss {
+    pub phase: usize,
+}
+
+/// Boss 的受击区域组件
+///
+/// 受击区域是跟随 Boss 移动的独立实体，激光命中区域时对 Boss 造成 `multiplier` 倍伤害
+#[derive(Component)]
+pub struct HitZone {
+    pub owner: Entity,
+    /// 相对 Boss 中心的偏移 (缩放前)
+    pub offset: Vec2,
+    pub multiplier: u32,
+}
+
+/// Boss 生命条组件
+#[derive(Component)]
+pub struct BossHealthBar;
+
+/// Boss 生命条的填充部分
+#[derive(Component)]
+struct BossHealthFill;
+
+/// Boss 被消灭后的连环爆炸
+#[derive(Component)]
+struct BossDeath {
+    timer: Timer,
+    /// 剩余的爆炸次数
+    remaining: u32,
+}
+
+/// Boss 被消灭后连环爆炸的次数
+const DEATH_EXPLOSIONS: u32 = 8;
+/// 连环爆炸的间隔 (秒)
+const DEATH_EXPLOSION_SECONDS: f32 = 0.12;
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        // 与普通敌人、网格的生成共用随机数，固定先后顺序保证结果可复现
+        app.add_system(
+            boss_spawn_system
+                .after(super::wave_system)
+                .after(super::enemy_spawn_system)
+                .after(super::grid::grid_spawn_system)
+                .in_set(GameSet::Spawn)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        )
+        .add_system(
+            hit_zone_follow_system
+                .after(super::enemy_movement_system)
+                .in_set(GameSet::Movement)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        )
+        // 在 damage_system 之前运行，敌人被消灭事件的处理系统都在 damage_system 之后
+        .add_systems(
+            (boss_damage_system, boss_death_system)
+                .chain()
+                .after(crate::player_enemy_collision_system)
+                .before(crate::damage_system)
+                .in_set(GameSet::Collision)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        )
+        .add_system(boss_bar_update_system);
+    }
+}
+
+/// Boss 生成系统，本波普通敌人全部被消灭后出现
+#[allow(clippy::too_many_arguments)]
+fn boss_spawn_system(
+    mut commands: Commands,
+    mut max_enemy: ResMut<MaxEnemy>,
+    mut wave_manager: ResMut<WaveManager>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    game_textures: Res<GameTextures>,
+    playfield: Res<Playfield>,
+) {
+    if !wave_manager.boss_ready(max_enemy.0) {
+        return;
+    }
+    wave_manager.boss_spawned();
+
+    let boss = &config.boss;
+    let scale = config.sprite_scale * boss.scale;
+    let (r, g, b) = boss.tint;
+    let start = Vec2::new(0., playfield.h / 2. + 100.);
+    let speed = wave_manager.wave().speed * boss.speed_scale;
+    let weapon = config.weapons.get(boss.weapon).clone();
+    let phase = &boss.phases[0];
+
+    let owner = commands
+        .spawn(SpriteBundle {
+            texture: game_textures.boss.clone(),
+            sprite: Sprite {
+                color: Color::rgb(r, g, b),
+                ..Default::default()
+            },
+            transform: Transform {
+                translation: start.extend(10.),
+                scale: Vec3::new(scale, scale, 1.),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(Boss { phase: 0 })
+        .insert(Health::new(boss.hit_points))
+        .insert(SpriteSize::from(boss.sprite.size))
+        .insert(Formation {
+            start: (start.x, start.y),
+            path: FormationPath::random(phase.path, &mut rng, &playfield, start),
+            offset: Vec2::ZERO,
+            speed,
+            progress: 0.,
+        })
+        .insert(boss_gun(boss, 0, &weapon, &mut rng))
+        .insert(weapon)
+        .insert(boss.weapon)
+        .id();
+
+    // 受击区域与 Boss 一起缩放
+    for zone in boss.zones.iter() {
+        let offset = Vec2::from(zone.offset);
+        commands.spawn((
+            TransformBundle::from_transform(Transform {
+                translation: (start + offset * scale).extend(10.),
+                scale: Vec3::new(scale, scale, 1.),
+                ..Default::default()
+            }),
+            Enemy,
+            SpriteSize::from(zone.size),
+            Collider::ENEMY,
+            HitZone {
+                owner,
+                offset,
+                multiplier: zone.multiplier,
+            },
+        ));
+    }
+
+    // 生命条，填充部分以左端为锚点按剩余生命值缩放宽度
+    let (bar_w, bar_h) = BOSS_BAR_SIZE;
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(1., 1., 1., 0.2),
+                custom_size: Some(Vec2::new(bar_w, bar_h)),
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(0., 0., 11.),
+            ..Default::default()
+        },
+        HudAnchor::new(Vec2::Y, Vec2::new(0., -70.)),
+        BossHealthBar,
+    ));
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgb(r, g, b),
+                custom_size: Some(Vec2::new(bar_w, bar_h)),
+                anchor: Anchor::CenterLeft,
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(0., 0., 12.),
+            ..Default::default()
+        },
+        HudAnchor::new(Vec2::Y, Vec2::new(-bar_w / 2., -70.)),
+        BossHealthBar,
+        BossHealthFill,
+    ));
+
+    max_enemy.0 += 1;
+}
+
+/// 第 `phase` 阶段的炮台，冷却时间不短于武器本身的冷却
+fn boss_gun(boss: &BossConfig, phase: usize, weapon: &Weapon, rng: &mut GameRng) -> EnemyGun {
+    let phase = &boss.phases[phase];
+    let cooldown = (1. / phase.fire_rate as f32).max(weapon.cooldown);
+    EnemyGun::new(phase.pattern, cooldown, rng)
+}
+
+/// 受击区域跟随系统，区域保持在 Boss 的相对位置上
+pub(crate) fn hit_zone_follow_system(
+    boss_query: Query<&Transform, (With<Boss>, Without<HitZone>)>,
+    mut zone_query: Query<(&mut Transform, &HitZone)>,
+) {
+    for (mut zone_tf, zone) in zone_query.iter_mut() {
+        let Ok(boss_tf) = boss_query.get(zone.owner) else {
+            continue;
+        };
+        let position = boss_tf.translation.xy() + zone.offset * boss_tf.scale.xy();
+        (zone_tf.translation.x, zone_tf.translation.y) = (position.x, position.y);
+    }
+}
+
+/// Boss 伤害结算系统
+///
+/// 生命值降到下一阶段的比例时切换移动路径与攻击方式，
+/// 生命值耗尽时发送敌人被消灭事件、移除受击区域并开始连环爆炸
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn boss_damage_system(
+    mut commands: Commands,
+    mut damage_events: EventReader<DamageEvent>,
+    mut killed_events: EventWriter<EnemyKilledEvent>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    playfield: Res<Playfield>,
+    mut query: Query<(
+        &Transform,
+        &mut Boss,
+        &mut Health,
+        &mut Sprite,
+        Option<&mut HitFlash>,
+        Option<&mut Formation>,
+        &Weapon,
+    )>,
+    zone_query: Query<(Entity, &HitZone)>,
+) {
+    let boss_config = &config.boss;
+    // 本次运行中开始闪烁的 Boss
+    let mut flashed = HashSet::new();
+    for event in damage_events.iter() {
+        let Ok((tf, mut boss, mut health, mut sprite, flash, formation, weapon)) =
+            query.get_mut(event.target)
+        else {
+            continue;
+        };
+        // 已被消灭，正在爆炸
+        if health.current == 0 {
+            continue;
+        }
+
+        if !health.damage(event.amount) {
+            match flash {
+                Some(mut flash) => flash.timer.reset(),
+                // 同一步长内已经开始闪烁，颜色已被替换
+                None if !flashed.insert(event.target) => {}
+                None => {
+                    commands
+                        .entity(event.target)
+                        .insert(HitFlash::new(sprite.color));
+                    sprite.color = HIT_FLASH_COLOR;
+                }
+            }
+
+            // 进入下一阶段，从当前位置开始新的路径
+            let phase = boss_config.phase(health.current as f32 / health.max as f32);
+            if phase > boss.phase {
+                boss.phase = phase;
+                let position = tf.translation.xy();
+                if let Some(mut formation) = formation {
+                    *formation = Formation {
+                        start: (position.x, position.y),
+                        path: FormationPath::random(
+                            boss_config.phases[phase].path,
+                            &mut rng,
+                            &playfield,
+                            position,
+                        ),
+                        offset: Vec2::ZERO,
+                        speed: formation.speed,
+                        progress: 0.,
+                    };
+                }
+                commands.entity(event.target).insert(boss_gun(
+                    boss_config,
+                    phase,
+                    weapon,
+                    &mut rng,
+                ));
+            }
+            continue;
+        }
+
+        // Boss 被消灭，得分与音效由敌人被消灭事件处理
+        killed_events.send(EnemyKilledEvent {
+            score: boss_config.score,
+            position: tf.translation,
+        });
+
+        for (zone_entity, zone) in zone_query.iter() {
+            if zone.owner == event.target {
+                commands.entity(zone_entity).despawn();
+            }
+        }
+        commands
+            .entity(event.target)
+            .remove::<(EnemyGun, Formation)>()
+            .insert(BossDeath {
+                timer: Timer::from_seconds(DEATH_EXPLOSION_SECONDS, TimerMode::Repeating),
+                remaining: DEATH_EXPLOSIONS,
+            });
+    }
+}
+
+/// Boss 连环爆炸系统，在 Boss 身上随机位置依次爆炸，最后一次爆炸后移除 Boss 与生命条
+fn boss_death_system(
+    mut commands: Commands,
+    fixed_time: Res<FixedTime>,
+    mut rng: ResMut<GameRng>,
+    mut max_enemy: ResMut<MaxEnemy>,
+    mut query: Query<(Entity, &Transform, &SpriteSize, &mut BossDeath)>,
+    bar_query: Query<Entity, With<BossHealthBar>>,
+) {
+    for (entity, tf, size, mut death) in query.iter_mut() {
+        if !death.timer.tick(fixed_time.period).just_finished() {
+            continue;
+        }
+
+        // Boss 图片范围内的随机位置
+        let half = size.0 * tf.scale.xy() / 2.;
+        let mut random_point = || {
+            let offset = Vec2::new(
+                rng.gen_range(-half.x..=half.x),
+                rng.gen_range(-half.y..=half.y),
+            );
+            (tf.translation.xy() + offset).extend(20.)
+        };
+        commands.spawn(ExplosionToSpawn(random_point()));
+        death.remaining -= 1;
+        if death.remaining > 0 {
+            continue;
+        }
+
+        // 最后在中心及四周同时爆炸
+        commands.spawn(ExplosionToSpawn(tf.translation));
+        for _ in 0..4 {
+            commands.spawn(ExplosionToSpawn(random_point()));
+        }
+        commands.entity(entity).despawn();
+        for bar in bar_query.iter() {
+            commands.entity(bar).despawn();
+        }
+        max_enemy.0 = max_enemy.0.saturating_sub(1);
+    }
+}
+
+/// Boss 生命条更新系统
+fn boss_bar_update_system(
+    boss_query: Query<&Health, With<Boss>>,
+    mut bar_query: Query<&mut Sprite, With<BossHealthFill>>,
+) {
+    let ratio = boss_query
+        .get_single()
+        .map_or(0., |health| health.current as f32 / health.max as f32);
+    for mut sprite in &mut bar_query {
+        sprite.custom_size = Some(Vec2::new(BOSS_BAR_SIZE.0 * ratio, BOSS_BAR_SIZE.1));
+    }
+}
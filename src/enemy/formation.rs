@@ -1,83 +1,427 @@
-use bevy::prelude::{Component, Resource};
-use rand::{thread_rng, Rng};
+use std::f32::consts::{PI, TAU};
 
-use crate::{WinSize, BASE_SPEED, FORMATION_MEMBER_MAX};
+use bevy::prelude::{Component, Resource, Vec2};
+use rand::Rng;
+
+use super::{
+    kind::EnemyKind,
+    wave::{FormationKind, Wave},
+};
+use crate::{config::GameConfig, resource::GameRng, Playfield};
 
 /// 敌人阵型
 #[derive(Component, Clone)]
 pub struct Formation {
     /// 启始位置
     pub start: (f32, f32),
-    /// 半径
-    pub radius: (f32, f32),
-    /// 原点
-    pub pivot: (f32, f32),
+    /// 移动路径
+    pub path: FormationPath,
+    /// 在阵型内的位置偏移
+    pub offset: Vec2,
     /// 速度
     pub speed: f32,
-    /// 角度
-    pub angle: f32,
+    /// 沿路径前进的距离
+    pub progress: f32,
+}
+
+impl Formation {
+    /// 从 `position` 出发追赶路径上前方的目标点，返回移动 `delta` 秒后的位置
+    ///
+    /// 两点间的距离公式 $|AB|=\sqrt{(x_1-x_2)^2+(y_1-y_2)^2}$
+    pub fn step(&mut self, position: Vec2, delta: f32, player: Option<Vec2>) -> Vec2 {
+        // 当前坐标
+        let (x_org, y_org) = (position.x, position.y);
+
+        // 单位时间内最大移动距离
+        let max_distance = self.speed * delta;
+
+        // 计算目标点位
+        let progress = self.progress + max_distance;
+        let dst = self.path.point(progress, player) + self.offset;
+        let (x_dst, y_dst) = (dst.x, dst.y);
+
+        // 计算距离
+        // 两点间的距离公式 根号下 a.x - b.x
+        let dx = x_org - x_dst;
+        let dy = y_org - y_dst;
+
+        let distance = (dx * dx + dy * dy).sqrt();
+        let distance_radio = if distance != 0. {
+            max_distance / distance
+        } else {
+            0.
+        };
+
+        // 计算 x y 的最终坐标
+        let x = x_org - dx * distance_radio;
+        let x = if dx > 0. { x.max(x_dst) } else { x.min(x_dst) };
+        let y = y_org - dy * distance_radio;
+        let y = if dy > 0. { y.max(y_dst) } else { y.min(y_dst) };
+
+        // 到达路径上 或接近路径时开始沿路径前进
+        if distance < max_distance * self.speed / 20. {
+            self.progress = progress;
+        }
+
+        Vec2::new(x, y)
+    }
+}
+
+/// 阵型路径
+///
+/// 路径由沿路径前进的距离换算出目标点，敌人以阵型速度追赶目标点，
+/// 接近目标点后才继续前进，因此距离不需要与曲线的实际长度完全一致
+#[derive(Clone, Debug)]
+pub enum FormationPath {
+    /// 绕原点的椭圆轨道
+    Ellipse {
+        /// 原点
+        pivot: Vec2,
+        /// 半径
+        radius: Vec2,
+        /// 初始角度
+        angle: f32,
+        /// 方向 1 逆时针 -1 顺时针
+        dir: f32,
+    },
+    /// 正弦波横扫：左右往返，同时上下摆动
+    Sine {
+        /// 中心点
+        center: Vec2,
+        /// 横扫范围的一半
+        half_width: f32,
+        /// 摆动幅度
+        amplitude: f32,
+        /// 波长
+        wavelength: f32,
+        /// 起始方向 1 向右 -1 向左
+        dir: f32,
+    },
+    /// 三次贝塞尔曲线，沿曲线往返
+    Bezier {
+        /// 控制点
+        points: [Vec2; 4],
+        /// 曲线长度的估计值
+        length: f32,
+    },
+    /// 经过全部控制点的闭合 Catmull-Rom 样条
+    Spline {
+        /// 控制点
+        points: Vec<Vec2>,
+        /// 相邻控制点间的平均距离
+        segment: f32,
+    },
+    /// 8 字形轨道
+    FigureEight {
+        /// 原点
+        pivot: Vec2,
+        /// 半径
+        radius: Vec2,
+    },
+    /// 俯冲：绕悬停点盘旋一段距离后冲向玩家，再返回悬停点
+    DiveBomb {
+        /// 悬停点
+        anchor: Vec2,
+        /// 盘旋半径
+        radius: f32,
+        /// 每轮盘旋的距离
+        hover: f32,
+        /// 俯冲的距离
+        depth: f32,
+    },
+    /// 网格行进：整体左右移动，到达边缘时下移一行，下移到底后逐行返回
+    Grid {
+        /// 起始行的中心点
+        origin: Vec2,
+        /// 横向移动范围的一半
+        half_width: f32,
+        /// 每次下移的距离
+        drop: f32,
+        /// 最多下移的行数
+        drops: u32,
+    },
+}
+
+impl FormationPath {
+    /// 沿路径前进 `distance` 后的目标点，`player` 为玩家当前的位置
+    pub fn point(&self, distance: f32, player: Option<Vec2>) -> Vec2 {
+        match self {
+            FormationPath::Ellipse {
+                pivot,
+                radius,
+                angle,
+                dir,
+            } => {
+                let angle = angle + dir * distance / (radius.x.min(radius.y) * PI / 2.);
+                *pivot + Vec2::new(radius.x * angle.cos(), radius.y * angle.sin())
+            }
+            FormationPath::Sine {
+                center,
+                half_width,
+                amplitude,
+                wavelength,
+                dir,
+            } => {
+                let x = ping_pong(distance / (2. * half_width)) * 2. - 1.;
+                let y = (TAU * distance / wavelength).sin();
+                *center + Vec2::new(dir * x * half_width, amplitude * y)
+            }
+            FormationPath::Bezier { points, length } => {
+                let t = ping_pong(distance / length);
+                let [p0, p1, p2, p3] = *points;
+                let u = 1. - t;
+                p0 * u * u * u + p1 * 3. * u * u * t + p2 * 3. * u * t * t + p3 * t * t * t
+            }
+            FormationPath::Spline { points, segment } => {
+                let n = points.len();
+                let u = (distance / segment).rem_euclid(n as f32);
+                let i = u as usize % n;
+                let t = u.fract();
+                let p0 = points[(i + n - 1) % n];
+                let p1 = points[i];
+                let p2 = points[(i + 1) % n];
+                let p3 = points[(i + 2) % n];
+                (p1 * 2.
+                    + (p2 - p0) * t
+                    + (p0 * 2. - p1 * 5. + p2 * 4. - p3) * t * t
+                    + (p1 * 3. - p0 - p2 * 3. + p3) * t * t * t)
+                    * 0.5
+            }
+            FormationPath::FigureEight { pivot, radius } => {
+                let angle = distance / (radius.x.min(radius.y) * PI / 2.);
+                *pivot + Vec2::new(radius.x * angle.sin(), radius.y * (2. * angle).sin())
+            }
+            FormationPath::DiveBomb {
+                anchor,
+                radius,
+                hover,
+                depth,
+            } => {
+                let cycle = distance % (hover + 2. * depth);
+                if cycle < *hover {
+                    // 盘旋
+                    let angle = cycle / radius;
+                    return *anchor + Vec2::new(radius * angle.cos(), radius * angle.sin());
+                }
+
+                // 冲向玩家，玩家不存在时冲向正下方
+                let target = player.unwrap_or(*anchor - Vec2::new(0., *depth));
+                let dive = cycle - hover;
+                let t = if dive < *depth {
+                    dive / depth
+                } else {
+                    2. - dive / depth
+                };
+                let start = *anchor + Vec2::new(*radius, 0.);
+                start + (target - start) * t
+            }
+            FormationPath::Grid {
+                origin,
+                half_width,
+                drop,
+                drops,
+            } => {
+                let leg = (2. * half_width).max(1.);
+                let legs = (distance / leg) as u32;
+                let x = (distance % leg) - half_width;
+                let x = if legs % 2 == 0 { x } else { -x };
+                let level = legs % (2 * drops).max(1);
+                let level = if level <= *drops {
+                    level
+                } else {
+                    2 * drops - level
+                };
+                *origin + Vec2::new(x, -drop * level as f32)
+            }
+        }
+    }
+
+    /// 按阵型类型随机生成路径，`start` 为敌人的起始位置
+    pub fn random(
+        kind: FormationKind,
+        rng: &mut GameRng,
+        playfield: &Playfield,
+        start: Vec2,
+    ) -> Self {
+        let (w, h) = (playfield.w, playfield.h);
+        // 从左侧进入时向右移动
+        let dir = if start.x < 0. { 1. } else { -1. };
+        match kind {
+            FormationKind::Wide | FormationKind::Circle | FormationKind::Tall => {
+                // 生成原点坐标
+                let w_spawn = w / 4.;
+                let h_spawn = h / 3. + 50.;
+                let pivot = Vec2::new(
+                    rng.gen_range(-w_spawn..w_spawn),
+                    rng.gen_range(0. ..h_spawn),
+                );
+
+                // 根据阵型类型生成半径
+                let ((x_min, x_max), (y_min, y_max)) = kind.radius_range();
+                let radius = Vec2::new(rng.gen_range(x_min..=x_max), rng.gen_range(y_min..=y_max));
+
+                // 计算初始角度
+                let angle = (start.y - pivot.y).atan2(start.x - pivot.x);
+                FormationPath::Ellipse {
+                    pivot,
+                    radius,
+                    angle,
+                    dir,
+                }
+            }
+            FormationKind::Sine => FormationPath::Sine {
+                center: Vec2::new(0., rng.gen_range(h / 6. ..h / 3.)),
+                half_width: w * 0.4,
+                amplitude: rng.gen_range(30. ..60.),
+                wavelength: rng.gen_range(150. ..250.),
+                dir,
+            },
+            FormationKind::Bezier => {
+                let x = w * 0.45;
+                let points = [
+                    Vec2::new(-dir * x, h * rng.gen_range(0.25..0.4)),
+                    Vec2::new(-dir * x / 2., h * rng.gen_range(-0.15..0.1)),
+                    Vec2::new(dir * x / 2., h * rng.gen_range(-0.15..0.1)),
+                    Vec2::new(dir * x, h * rng.gen_range(0.25..0.4)),
+                ];
+                // 控制点折线的长度不小于曲线长度
+                let length = points.windows(2).map(|p| p[0].distance(p[1])).sum();
+                FormationPath::Bezier { points, length }
+            }
+            FormationKind::Spline => {
+                let count = rng.gen_range(4..=6);
+                let points: Vec<Vec2> = (0..count)
+                    .map(|_| Vec2::new(w * rng.gen_range(-0.4..0.4), h * rng.gen_range(-0.1..0.4)))
+                    .collect();
+                let segment = (0..count)
+                    .map(|i| points[i].distance(points[(i + 1) % count]))
+                    .sum::<f32>()
+                    / count as f32;
+                FormationPath::Spline {
+                    points,
+                    segment: segment.max(1.),
+                }
+            }
+            FormationKind::FigureEight => FormationPath::FigureEight {
+                pivot: Vec2::new(
+                    rng.gen_range(-w / 6. ..w / 6.),
+                    rng.gen_range(h / 8. ..h / 4.),
+                ),
+                radius: Vec2::new(w * rng.gen_range(0.2..0.35), h * rng.gen_range(0.08..0.15)),
+            },
+            FormationKind::DiveBomb => {
+                let anchor = Vec2::new(
+                    rng.gen_range(-w / 4. ..w / 4.),
+                    rng.gen_range(h / 6. ..h / 3.),
+                );
+                FormationPath::DiveBomb {
+                    anchor,
+                    radius: 40.,
+                    hover: rng.gen_range(400. ..700.),
+                    depth: anchor.y + h / 2. - 40.,
+                }
+            }
+            FormationKind::Grid => FormationPath::Grid {
+                origin: Vec2::new(0., rng.gen_range(h / 6. ..h / 3.)),
+                half_width: w * 0.37,
+                drop: 30.,
+                drops: 3,
+            },
+        }
+    }
+}
+
+/// 将 `t` 映射为在 0 和 1 之间往返的值
+fn ping_pong(t: f32) -> f32 {
+    let t = t.rem_euclid(2.);
+    if t <= 1. {
+        t
+    } else {
+        2. - t
+    }
 }
 
 /// 阵型资源
 #[derive(Resource, Default)]
 pub struct FormationMaker {
-    /// 当前阵型
-    current_template: Option<Formation>,
+    /// 当前阵型及阵型内的敌人类型
+    current_template: Option<(Formation, EnemyKind)>,
     /// 当前数量
     current_members: u32,
 }
 
 impl FormationMaker {
-    pub fn make(&mut self, win_size: &WinSize) -> Formation {
+    /// 生成阵型，同一阵型内的敌人类型相同
+    pub fn make(
+        &mut self,
+        rng: &mut GameRng,
+        playfield: &Playfield,
+        wave: &Wave,
+        config: &GameConfig,
+    ) -> (Formation, EnemyKind) {
         match (
             &self.current_template,
-            self.current_members >= FORMATION_MEMBER_MAX,
+            self.current_members >= wave.formation_members,
         ) {
             // 当前阵型还有空位 直接加入
-            (Some(template), false) => {
+            (Some((template, kind)), false) => {
+                let mut formation = template.clone();
+                // 网格阵型的成员横向排开
+                if let FormationPath::Grid { .. } = formation.path {
+                    formation.offset.x = grid_offset(self.current_members, wave.formation_members);
+                }
                 self.current_members += 1;
-                template.clone()
+                (formation, *kind)
             }
             // 当前阵型没有空位，或还没有阵型，需要创建新的阵型
             _ => {
-                let mut rng = thread_rng();
+                // 敌人类型
+                let kind = EnemyKind::pick(rng, wave.kinds);
 
                 // 生成 起点坐标
-                let w_spawn = win_size.w / 2. + 100.;
-                let h_spawn = win_size.h / 2. + 100.;
+                let w_spawn = playfield.w / 2. + 100.;
+                let h_spawn = playfield.h / 2. + 100.;
                 let x = if rng.gen_bool(0.5) { w_spawn } else { -w_spawn };
                 let y = rng.gen_range(-h_spawn..h_spawn);
                 let start = (x, y);
 
-                // 生成原点坐标
-                let w_spawn = win_size.w / 4.;
-                let h_spawn = win_size.h / 3. + 50.;
-                let pivot = (
-                    rng.gen_range(-w_spawn..w_spawn),
-                    rng.gen_range(0. ..h_spawn),
-                );
-
-                // 生成半径
-                let radius = (rng.gen_range(80. ..150.), 100.);
-
-                // 计算初始角度
-                let angle = (y - pivot.1).atan2(x - pivot.0);
+                // 从本波的阵型类型中随机选择路径
+                let formation_kind = wave.formations[rng.gen_range(0..wave.formations.len())];
+                let mut path =
+                    FormationPath::random(formation_kind, rng, playfield, Vec2::new(x, y));
+                if let FormationPath::Grid { half_width, .. } = &mut path {
+                    // 给横向排开的成员留出位置
+                    let span = grid_offset(wave.formation_members - 1, wave.formation_members);
+                    *half_width = (*half_width - span).max(0.);
+                }
+                let offset = match path {
+                    FormationPath::Grid { .. } => {
+                        Vec2::new(grid_offset(0, wave.formation_members), 0.)
+                    }
+                    _ => Vec2::ZERO,
+                };
 
                 // 速度
-                let speed = BASE_SPEED;
+                let speed = wave.speed * config.enemies.get(kind).speed_scale;
 
                 let formation = Formation {
                     start,
-                    pivot,
-                    radius,
-                    angle,
+                    path,
+                    offset,
                     speed,
+                    progress: 0.,
                 };
 
-                self.current_template = Some(formation.clone());
+                self.current_template = Some((formation.clone(), kind));
                 self.current_members = 1;
-                formation
+                (formation, kind)
             }
         }
     }
 }
+
+/// 网格阵型中第 `index` 个成员的横向偏移，`members` 个成员以原点为中心排开
+fn grid_offset(index: u32, members: u32) -> f32 {
+    const SPACING: f32 = 70.;
+    (index as f32 - (members.max(1) - 1) as f32 / 2.) * SPACING
+}
@@ -0,0 +1,279 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::wave::WaveManager;
+use crate::{
+    collision::{shape::ColliderShapes, Collider},
+    components::{Enemy, FromEnemy, Health, Player, SpriteSize},
+    config::{GameConfig, GameMode},
+    events::PlayerKilledEvent,
+    resource::{GameClock, GameData, GameRng, GameState, PlayerState},
+    weapon::{Weapon, WeaponKind},
+    GameSet, GameTextures, MaxEnemy, Playfield,
+};
+
+/// 网格成员组件，记录所在的列
+#[derive(Component)]
+pub struct GridMember {
+    pub column: u32,
+}
+
+/// 网格行进资源，整个网格共用同一个节拍与方向
+#[derive(Resource)]
+pub struct GridMarch {
+    /// 方向 1 向右 -1 向左
+    dir: f32,
+    /// 节拍计时，每拍整体移动一次
+    beat: Timer,
+    /// 攻击计时
+    fire: Timer,
+    /// 平均攻击间隔 (秒)，攻击频率为 0 时网格不攻击
+    fire_interval: Option<f32>,
+}
+
+impl Default for GridMarch {
+    fn default() -> Self {
+        Self {
+            dir: 1.,
+            beat: Timer::from_seconds(1., TimerMode::Once),
+            fire: Timer::from_seconds(1., TimerMode::Once),
+            fire_interval: Some(1.),
+        }
+    }
+}
+
+pub struct GridPlugin;
+
+impl Plugin for GridPlugin {
+    fn build(&self, app: &mut App) {
+        // 只在网格模式下运行，波次横幅与波次切换沿用 wave_system
+        app.init_resource::<GridMarch>()
+            .add_system(
+                grid_spawn_system
+                    .after(super::wave_system)
+                    .after(super::enemy_spawn_system)
+                    .in_set(GameSet::Spawn)
+                    .run_if(grid_mode)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                grid_fire_system
+                    .after(super::enemy_fire_system)
+                    .in_set(GameSet::Fire)
+                    .run_if(grid_mode)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                grid_march_system
+                    .after(crate::player::player_movable_system)
+                    .after(super::enemy_movement_system)
+                    .before(super::boss::hit_zone_follow_system)
+                    .in_set(GameSet::Movement)
+                    .run_if(grid_mode)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            );
+    }
+}
+
+/// 是否为网格模式
+pub fn grid_mode(config: Res<GameConfig>) -> bool {
+    config.mode == GameMode::Grid
+}
+
+/// 网格生成系统，每波横幅结束后一次生成整个网格
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn grid_spawn_system(
+    mut commands: Commands,
+    mut max_enemy: ResMut<MaxEnemy>,
+    mut wave_manager: ResMut<WaveManager>,
+    mut grid_march: ResMut<GridMarch>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    game_textures: Res<GameTextures>,
+    collider_shapes: Res<ColliderShapes>,
+    playfield: Res<Playfield>,
+) {
+    if !wave_manager.can_spawn(max_enemy.0) {
+        return;
+    }
+
+    let grid = &config.grid;
+    let (x_spacing, y_spacing) = grid.spacing;
+    let top = playfield.h / 2. - grid.top;
+    for (row, &kind) in grid.rows.iter().enumerate() {
+        let stats = config.enemies.get(kind);
+        let scale = config.sprite_scale * stats.scale;
+        let (r, g, b) = stats.tint;
+        for column in 0..grid.columns {
+            let x = (column as f32 - (grid.columns - 1) as f32 / 2.) * x_spacing;
+            let y = top - row as f32 * y_spacing;
+
+            let mut enemy = commands.spawn(SpriteBundle {
+                texture: game_textures.enemy(kind),
+                sprite: Sprite {
+                    color: Color::rgb(r, g, b),
+                    flip_y: stats.flip_y,
+                    ..Default::default()
+                },
+                transform: Transform {
+                    translation: Vec3::new(x, y, 10.),
+                    scale: Vec3::new(scale, scale, 1.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+            enemy
+                .insert(Enemy)
+                .insert(kind)
+                .insert(Health::new(stats.hit_points))
+                .insert(GridMember { column })
+                .insert(SpriteSize::from(stats.sprite.size))
+                .insert(Collider::ENEMY)
+                .insert(collider_shapes.get(&stats.sprite).flip_y(stats.flip_y));
+            if let Some(weapon_kind) = stats.weapon {
+                enemy
+                    .insert(config.weapons.get(weapon_kind).clone())
+                    .insert(weapon_kind);
+            }
+            max_enemy.0 += 1;
+        }
+    }
+    wave_manager.spawned_all();
+
+    // 后面的波次节拍更快、攻击更频繁
+    let wave = wave_manager.wave();
+    let beat = grid.beat_seconds * config.base_speed / wave.speed;
+    let fire_interval = (wave.fire_rate > 0.).then(|| (1. / wave.fire_rate) as f32);
+    let first_fire = fire_interval.map_or(0., |interval| interval * rng.gen_range(0.5..1.5));
+    *grid_march = GridMarch {
+        dir: 1.,
+        beat: Timer::from_seconds(beat, TimerMode::Once),
+        fire: Timer::from_seconds(first_fire, TimerMode::Once),
+        fire_interval,
+    };
+}
+
+/// 网格攻击系统，每次随机选择一列，由该列最下方的敌人攻击
+///
+/// 最下方的敌人没有武器时该列不攻击，上方的敌人不会隔着它开火
+#[allow(clippy::type_complexity)]
+fn grid_fire_system(
+    mut commands: Commands,
+    fixed_time: Res<FixedTime>,
+    mut rng: ResMut<GameRng>,
+    mut grid_march: ResMut<GridMarch>,
+    config: Res<GameConfig>,
+    game_textures: Res<GameTextures>,
+    query: Query<(&Transform, &GridMember, Option<(&Weapon, &WeaponKind)>), With<Enemy>>,
+) {
+    let Some(fire_interval) = grid_march.fire_interval else {
+        return;
+    };
+    if !grid_march.fire.tick(fixed_time.period).finished() {
+        return;
+    }
+    let cooldown = fire_interval * rng.gen_range(0.75..1.25);
+    grid_march.fire = Timer::from_seconds(cooldown, TimerMode::Once);
+
+    // 每列最下方的敌人，包括没有武器的敌人
+    let mut bottom: Vec<(&Transform, &GridMember, Option<(&Weapon, &WeaponKind)>)> = Vec::new();
+    for item in query.iter() {
+        match bottom
+            .iter_mut()
+            .find(|other| other.1.column == item.1.column)
+        {
+            Some(other) if other.0.translation.y > item.0.translation.y => *other = item,
+            Some(_) => {}
+            None => bottom.push(item),
+        }
+    }
+    let armed: Vec<(&Transform, &Weapon, &WeaponKind)> = bottom
+        .into_iter()
+        .filter_map(|(tf, _, armed)| armed.map(|(weapon, kind)| (tf, weapon, kind)))
+        .collect();
+    if armed.is_empty() {
+        return;
+    }
+
+    let (enemy_tf, weapon, &weapon_kind) = armed[rng.gen_range(0..armed.len())];
+    weapon.fire(
+        &mut commands,
+        game_textures.weapon(weapon_kind),
+        enemy_tf.translation.truncate().extend(1.),
+        PI,
+        config.sprite_scale,
+        FromEnemy,
+    );
+}
+
+/// 网格行进系统
+///
+/// 每拍整体横向移动一步，即将越过窗口边缘时改为下移并掉头，同时加快节拍。
+/// 网格下移到玩家所在的行时游戏结束
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(crate) fn grid_march_system(
+    mut commands: Commands,
+    fixed_time: Res<FixedTime>,
+    clock: Res<GameClock>,
+    config: Res<GameConfig>,
+    playfield: Res<Playfield>,
+    mut killed_events: EventWriter<PlayerKilledEvent>,
+    mut grid_march: ResMut<GridMarch>,
+    mut game_data: ResMut<GameData>,
+    mut player_state: ResMut<PlayerState>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut query: Query<(&mut Transform, &SpriteSize), (With<GridMember>, Without<Player>)>,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+) {
+    if !grid_march.beat.tick(fixed_time.period).finished() {
+        return;
+    }
+
+    let grid = &config.grid;
+    let half_w = playfield.w / 2.;
+
+    // 移动方向上最外侧的边缘，乘以方向后统一按向右比较
+    let dir = grid_march.dir;
+    let edge = query
+        .iter()
+        .map(|(tf, size)| dir * tf.translation.x + size.0.x * tf.scale.x / 2.)
+        .reduce(f32::max);
+    let Some(edge) = edge else {
+        return;
+    };
+
+    let mut beat = grid_march.beat.duration().as_secs_f32();
+    if edge + grid.step > half_w {
+        // 到达边缘：下移、掉头并加快节拍
+        for (mut tf, _) in query.iter_mut() {
+            tf.translation.y -= grid.drop;
+        }
+        grid_march.dir = -dir;
+        beat *= grid.speed_up;
+    } else {
+        for (mut tf, _) in query.iter_mut() {
+            tf.translation.x += dir * grid.step;
+        }
+    }
+    grid_march.beat = Timer::from_seconds(beat, TimerMode::Once);
+
+    // 网格最下方到达玩家所在的行
+    let bottom = query
+        .iter()
+        .map(|(tf, size)| tf.translation.y - size.0.y * tf.scale.y / 2.)
+        .fold(f32::MAX, f32::min);
+    let player_row = -playfield.h / 2. + 5. + config.sprites.player.size.1 * config.sprite_scale;
+    if bottom <= player_row {
+        if let Ok((entity, player_tf)) = player_query.get_single() {
+            commands.entity(entity).despawn();
+            player_state.shot(clock.elapsed_seconds_f64());
+            killed_events.send(PlayerKilledEvent {
+                position: player_tf.translation,
+            });
+        }
+        game_data.lose_all_lives();
+        next_state.set(GameState::GameOver);
+    }
+}
@@ -0,0 +1,42 @@
+use bevy::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+use crate::resource::GameRng;
+
+/// 敌人类型组件
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EnemyKind {
+    /// 侦察机：速度快、生命少
+    Scout,
+    /// 炮艇：发射双发激光
+    Gunner,
+    /// 坦克：速度慢、生命多、分数高
+    Tank,
+    /// 自杀机：速度最快，不发射激光
+    Kamikaze,
+}
+
+impl EnemyKind {
+    /// 全部敌人类型
+    pub const ALL: [EnemyKind; 4] = [
+        EnemyKind::Scout,
+        EnemyKind::Gunner,
+        EnemyKind::Tank,
+        EnemyKind::Kamikaze,
+    ];
+
+    /// 配置文件中对应的字段名
+    pub fn config_key(&self) -> &'static str {
+        match self {
+            EnemyKind::Scout => "scout",
+            EnemyKind::Gunner => "gunner",
+            EnemyKind::Tank => "tank",
+            EnemyKind::Kamikaze => "kamikaze",
+        }
+    }
+
+    /// 按权重随机选择敌人类型，权重全部为 0 时返回侦察机
+    pub fn pick(rng: &mut GameRng, weights: &[(EnemyKind, u32)]) -> Self {
+        rng.pick_weighted(weights).unwrap_or(EnemyKind::Scout)
+    }
+}
@@ -1,18 +1,32 @@
 use std::{f32::consts::PI, time::Duration};
 
 use crate::{
-    components::{Enemy, FromEnemy, Laser, Movable, SpriteSize, Velocity},
-    resource::GameState,
-    GameTextures, MaxEnemy, WinSize, ENEMY_LASER_SIZE, ENEMY_SIZE, MAX_ENEMY, SPRITE_SCALE,
-    TIME_STEP,
+    collision::{shape::ColliderShapes, Collider},
+    components::{Enemy, FromEnemy, Health, Player, SpriteSize, WaveBanner},
+    config::GameConfig,
+    resource::{GameData, GameRng, GameState},
+    weapon::{Weapon, WeaponKind},
+    GameSet, GameTextures, MaxEnemy, Playfield,
 };
 
-use bevy::{prelude::*, time::common_conditions::on_timer};
-use rand::{thread_rng, Rng};
+use bevy::{prelude::*, time::common_conditions::on_fixed_timer};
 
-use self::formation::{Formation, FormationMaker};
+use self::{
+    boss::{Boss, BossPlugin},
+    formation::{Formation, FormationMaker},
+    grid::{grid_mode, GridPlugin},
+    pattern::EnemyGun,
+    ram::RamPlugin,
+    wave::WaveManager,
+};
 
-mod formation;
+pub mod boss;
+pub mod formation;
+pub mod grid;
+pub mod kind;
+pub mod pattern;
+pub mod ram;
+pub mod wave;
 
 #[derive(Component)]
 pub struct EnemyPlugin;
@@ -20,158 +34,238 @@ pub struct EnemyPlugin;
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         // 间隔执行
-        app.insert_resource(FormationMaker::default())
+        app.add_plugin(GridPlugin)
+            .add_plugin(BossPlugin)
+            .add_plugin(RamPlugin)
+            .insert_resource(FormationMaker::default())
+            .init_resource::<WaveManager>()
+            .add_system(wave_reset_system.in_schedule(OnExit(GameState::Welcome)))
+            .add_system(
+                wave_system
+                    .before(enemy_spawn_system)
+                    .in_set(GameSet::Spawn)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
             .add_system(
                 enemy_spawn_system
-                    .run_if(on_timer(Duration::from_secs_f32(0.5)))
-                    .in_set(OnUpdate(GameState::InGame)),
+                    .run_if(not(grid_mode))
+                    .run_if(on_fixed_timer(Duration::from_secs_f32(0.5)))
+                    .in_set(GameSet::Spawn)
+                    .in_schedule(CoreSchedule::FixedUpdate),
             )
             .add_system(
                 enemy_fire_system
-                    .run_if(enemy_fire_criteria)
-                    .in_set(OnUpdate(GameState::InGame)),
+                    .in_set(GameSet::Fire)
+                    .in_schedule(CoreSchedule::FixedUpdate),
             )
-            .add_system(enemy_movement_system.in_set(OnUpdate(GameState::InGame)));
+            .add_system(
+                enemy_movement_system
+                    // 路径上的目标点与玩家的位置有关，在玩家移动之后运行
+                    .after(crate::player::player_movable_system)
+                    .in_set(GameSet::Movement)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            );
+    }
+}
+
+/// 开始新的一局时从第一波开始
+fn wave_reset_system(
+    config: Res<GameConfig>,
+    mut wave_manager: ResMut<WaveManager>,
+    mut formation_maker: ResMut<FormationMaker>,
+) {
+    *wave_manager = WaveManager::start(1, &config);
+    *formation_maker = FormationMaker::default();
+}
+
+/// 波次系统，网格模式下同样负责横幅展示与波次切换
+///
+/// 每波开始前展示 "WAVE N" 横幅，本波敌人全部被消灭后进入下一波
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn wave_system(
+    mut commands: Commands,
+    fixed_time: Res<FixedTime>,
+    config: Res<GameConfig>,
+    game_textures: Res<GameTextures>,
+    max_enemy: Res<MaxEnemy>,
+    mut game_data: ResMut<GameData>,
+    mut wave_manager: ResMut<WaveManager>,
+    mut formation_maker: ResMut<FormationMaker>,
+    banner_query: Query<Entity, With<WaveBanner>>,
+) {
+    if let Some(timer) = wave_manager.banner_mut() {
+        // 横幅展示
+        if timer.elapsed().is_zero() {
+            commands.spawn((
+                Text2dBundle {
+                    text: Text::from_section(
+                        format!("WAVE {}", game_data.get_wave()),
+                        TextStyle {
+                            font: game_textures.font.clone(),
+                            font_size: 46.,
+                            color: Color::ANTIQUE_WHITE,
+                        },
+                    )
+                    .with_alignment(TextAlignment::Center),
+                    transform: Transform::from_xyz(0., 0., 11.),
+                    ..Default::default()
+                },
+                WaveBanner,
+            ));
+        }
+
+        timer.tick(fixed_time.period);
+        if timer.finished() {
+            for entity in banner_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            wave_manager.finish_banner();
+        }
+        return;
+    }
+
+    // 本波结束，进入下一波
+    if wave_manager.cleared(max_enemy.0) {
+        game_data.next_wave();
+        *wave_manager = WaveManager::start(game_data.get_wave(), &config);
+        *formation_maker = FormationMaker::default();
     }
 }
 
 /// 敌人生成系统
+#[allow(clippy::too_many_arguments)]
 fn enemy_spawn_system(
     mut commands: Commands,
     mut max_enemy: ResMut<MaxEnemy>,
     mut formation_maker: ResMut<FormationMaker>,
+    mut wave_manager: ResMut<WaveManager>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
     game_textures: Res<GameTextures>,
-    win_size: Res<WinSize>,
+    collider_shapes: Res<ColliderShapes>,
+    playfield: Res<Playfield>,
 ) {
-    // 如果当前的敌人数量大于等于最大敌人数量，则不再产生新的敌人
-    if max_enemy.0 >= MAX_ENEMY {
+    // 横幅展示期间、本波敌人已全部生成或当前敌人数量达到上限时，不再产生新的敌人
+    if !wave_manager.can_spawn(max_enemy.0) {
         return;
     }
 
     // 随机生成
     // let mut rng = thread_rng();
-    // let w_span = win_size.w / 2. - 100.;
-    // let h_span = win_size.h / 2. - 100.;
+    // let w_span = playfield.w / 2. - 100.;
+    // let h_span = playfield.h / 2. - 100.;
     // let x = rng.gen_range(-w_span..w_span);
     // let y = rng.gen_range(-h_span..h_span);
 
     // 使用 阵型
-    let formation = formation_maker.make(&win_size);
+    let (formation, kind) =
+        formation_maker.make(&mut rng, &playfield, wave_manager.wave(), &config);
     let (x, y) = formation.start;
+    let stats = config.enemies.get(kind);
+    let scale = config.sprite_scale * stats.scale;
+    let (r, g, b) = stats.tint;
 
-    commands
-        .spawn(SpriteBundle {
-            texture: game_textures.enemy.clone(),
-            transform: Transform {
-                // 坐标
-                translation: Vec3::new(x, y, 10.),
-                // 缩放
-                scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.),
-                // 旋转
-                rotation: Quat::IDENTITY,
-            },
+    let mut enemy = commands.spawn(SpriteBundle {
+        texture: game_textures.enemy(kind),
+        sprite: Sprite {
+            color: Color::rgb(r, g, b),
+            flip_y: stats.flip_y,
             ..Default::default()
-        })
+        },
+        transform: Transform {
+            // 坐标
+            translation: Vec3::new(x, y, 10.),
+            // 缩放
+            scale: Vec3::new(scale, scale, 1.),
+            // 旋转
+            rotation: Quat::IDENTITY,
+        },
+        ..Default::default()
+    });
+    enemy
         .insert(Enemy)
+        .insert(kind)
+        .insert(Health::new(stats.hit_points))
         .insert(formation)
-        .insert(SpriteSize::from(ENEMY_SIZE));
+        .insert(SpriteSize::from(stats.sprite.size))
+        .insert(Collider::ENEMY)
+        .insert(collider_shapes.get(&stats.sprite).flip_y(stats.flip_y));
+    // 攻击频率为 0 的敌人不攻击，冷却时间不短于武器本身的冷却
+    let fire_rate = wave_manager.wave().fire_rate * stats.fire_rate_scale;
+    if let (Some(weapon_kind), true) = (stats.weapon, fire_rate > 0.) {
+        let weapon = config.weapons.get(weapon_kind).clone();
+        let cooldown = (1. / fire_rate as f32).max(weapon.cooldown);
+        enemy
+            .insert(EnemyGun::new(stats.pattern, cooldown, &mut rng))
+            .insert(weapon)
+            .insert(weapon_kind);
+    }
     max_enemy.0 += 1;
+    wave_manager.spawned();
 }
 
 /// 敌人射击系统
+///
+/// 每个敌人按各自的攻击方式与冷却计时发射，瞄准类攻击朝向玩家当前的位置
+#[allow(clippy::type_complexity)]
 fn enemy_fire_system(
     mut commands: Commands,
+    fixed_time: Res<FixedTime>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
     game_textures: Res<GameTextures>,
-    query: Query<&Transform, With<Enemy>>,
+    player_query: Query<&Transform, With<Player>>,
+    mut query: Query<
+        (&Transform, &mut EnemyGun, &Weapon, &WeaponKind),
+        Or<(With<Enemy>, With<Boss>)>,
+    >,
 ) {
-    for &enemy_tf in query.iter() {
-        let (x, y) = (enemy_tf.translation.x, enemy_tf.translation.y);
-
-        commands
-            .spawn(SpriteBundle {
-                texture: game_textures.enemy_laser.clone(),
-                transform: Transform {
-                    translation: Vec3::new(x, y, 1.),
-                    scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.),
-                    rotation: Quat::from_rotation_x(PI),
-                },
-                ..Default::default()
-            })
-            .insert(Laser)
-            .insert(SpriteSize::from(ENEMY_LASER_SIZE))
-            .insert(FromEnemy)
-            .insert(Movable { auto_despawn: true })
-            .insert(Velocity::new(0., -1.));
-    }
-}
+    let player = player_query
+        .get_single()
+        .ok()
+        .map(|tf| tf.translation.truncate());
 
-/// 是否发射攻击
-fn enemy_fire_criteria() -> bool {
-    if thread_rng().gen_bool(1. / 60.) {
-        true
-    } else {
-        false
+    for (enemy_tf, mut gun, weapon, &weapon_kind) in query.iter_mut() {
+        let origin = enemy_tf.translation.truncate();
+        // 玩家不存在时向正下方瞄准
+        let aim = match player {
+            Some(target) => {
+                let to_target = target - origin;
+                to_target.x.atan2(to_target.y)
+            }
+            None => PI,
+        };
+
+        for angle in gun.tick(fixed_time.period, &mut rng, aim) {
+            weapon.fire(
+                &mut commands,
+                game_textures.weapon(weapon_kind),
+                origin.extend(1.),
+                angle,
+                config.sprite_scale,
+                FromEnemy,
+            );
+        }
     }
 }
 
 /// 敌人移动系统
 ///
-/// 两点间的距离公式 $|AB|=\sqrt{(x_1-x_2)^2+(y_1-y_2)^2}$
-fn enemy_movement_system(mut query: Query<(&mut Transform, &mut Formation), With<Enemy>>) {
-    // 当前时间
-    // let now = time.elapsed_seconds();
+/// 敌人追赶阵型路径上前方的目标点，接近目标点后沿路径继续前进
+#[allow(clippy::type_complexity)]
+pub(crate) fn enemy_movement_system(
+    fixed_time: Res<FixedTime>,
+    player_query: Query<&Transform, (With<Player>, Without<Formation>)>,
+    mut query: Query<(&mut Transform, &mut Formation), Or<(With<Enemy>, With<Boss>)>>,
+) {
+    let delta = fixed_time.period.as_secs_f32();
+    let player = player_query
+        .get_single()
+        .ok()
+        .map(|tf| tf.translation.truncate());
     for (mut transform, mut formation) in query.iter_mut() {
-        // 当前坐标
-        let (x_org, y_org) = (transform.translation.x, transform.translation.y);
-        // let (x_org, y_org) = formation.start;
-
-        // 单位时间内最大移动距离
-        // let max_distance = BASE_SPEED * TIME_STEP;
-        let max_distance = formation.speed * TIME_STEP;
-
-        // 方向 1 顺时针 -1 逆时针
-        // let dir = -1.;
-        let dir = if formation.start.0 < 0. { 1. } else { -1. };
-        // 中心点
-        // let (x_pivot, y_pivot) = (0., 0.);
-        let (x_pivot, y_pivot) = formation.pivot;
-        // 半径
-        // let (x_radius, y_radius) = (200., 130.);
-        let (x_radius, y_radius) = formation.radius;
-
-        // 基于当前时间计算的角度
-        // let angel = dir * BASE_SPEED * TIME_STEP * now % 360. / PI;
-        let angel = formation.angle
-            + dir * formation.speed * TIME_STEP / (x_radius.min(y_radius) * PI / 2.);
-
-        // 计算目标点位
-        let x_dst = x_radius * angel.cos() + x_pivot;
-        let y_dst = y_radius * angel.sin() + y_pivot;
-
-        // 计算距离
-        // 两点间的距离公式 根号下 a.x - b.x
-        let dx = x_org - x_dst;
-        let dy = y_org - y_dst;
-
-        let distance = (dx * dx + dy * dy).sqrt();
-        let distance_radio = if distance != 0. {
-            max_distance / distance
-        } else {
-            0.
-        };
-
-        // 计算 x y 的最终坐标
-        let x = x_org - dx * distance_radio;
-        let x = if dx > 0. { x.max(x_dst) } else { x.min(x_dst) };
-        let y = y_org - dy * distance_radio;
-        let y = if dy > 0. { y.max(y_dst) } else { y.min(y_dst) };
-
-        // 图片资源在椭圆上 或接近椭圆时开始加入旋转
-        if distance < max_distance * formation.speed / 20. {
-            formation.angle = angel;
-        }
-
+        let position = formation.step(transform.translation.truncate(), delta, player);
         let translation = &mut transform.translation;
-        (translation.x, translation.y) = (x, y);
+        (translation.x, translation.y) = (position.x, position.y);
     }
 }
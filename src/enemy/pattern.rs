@@ -0,0 +1,98 @@
+use std::{
+    f32::consts::{PI, TAU},
+    time::Duration,
+};
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::resource::GameRng;
+
+/// 敌人攻击方式
+///
+/// 方向均为与正上方的夹角 (弧度)，向右为正，每个方向调用一次武器发射
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum FirePattern {
+    /// 向正下方发射
+    #[default]
+    Straight,
+    /// 瞄准玩家当前的位置
+    Aimed,
+    /// 环形弹幕，向四周均匀发射 `count` 个方向
+    Radial { count: u32 },
+    /// 螺旋弹幕，均匀发射 `arms` 个方向，每次发射后旋转 `step` 度
+    Spiral { arms: u32, step: f32 },
+    /// 齐射，瞄准玩家连续发射 `shots` 次，每次间隔 `interval` 秒
+    Volley { shots: u32, interval: f32 },
+}
+
+/// 敌人炮台组件，记录攻击方式与各自的冷却计时
+#[derive(Component)]
+pub struct EnemyGun {
+    pattern: FirePattern,
+    /// 两次攻击之间的平均冷却时间 (秒)
+    cooldown: f32,
+    timer: Timer,
+    /// 螺旋弹幕当前的起始方向
+    spiral_angle: f32,
+    /// 本轮齐射剩余的次数
+    volley_left: u32,
+}
+
+impl EnemyGun {
+    /// 创建炮台，首次攻击的时间随机错开，避免同时生成的敌人一起攻击
+    pub fn new(pattern: FirePattern, cooldown: f32, rng: &mut GameRng) -> Self {
+        Self {
+            pattern,
+            cooldown,
+            timer: Timer::from_seconds(cooldown * rng.gen_range(0.5..1.5), TimerMode::Once),
+            spiral_angle: PI,
+            volley_left: 0,
+        }
+    }
+
+    /// 推进冷却计时，返回本次需要发射的方向，冷却未结束时为空
+    ///
+    /// `aim` 为瞄准玩家的方向
+    pub fn tick(&mut self, delta: Duration, rng: &mut GameRng, aim: f32) -> Vec<f32> {
+        if !self.timer.tick(delta).finished() {
+            return Vec::new();
+        }
+
+        let angles = match self.pattern {
+            FirePattern::Straight => vec![PI],
+            FirePattern::Aimed => vec![aim],
+            FirePattern::Radial { count } => spread_evenly(PI, count),
+            FirePattern::Spiral { arms, step } => {
+                let angles = spread_evenly(self.spiral_angle, arms);
+                self.spiral_angle = (self.spiral_angle + step.to_radians()) % TAU;
+                angles
+            }
+            FirePattern::Volley { shots, interval } => {
+                if self.volley_left == 0 {
+                    self.volley_left = shots;
+                }
+                self.volley_left -= 1;
+                if self.volley_left > 0 {
+                    // 齐射未结束，按间隔继续发射
+                    self.timer = Timer::from_seconds(interval, TimerMode::Once);
+                    return vec![aim];
+                }
+                vec![aim]
+            }
+        };
+
+        // 冷却时间上下浮动，避免敌人的攻击节奏逐渐同步
+        let cooldown = self.cooldown * rng.gen_range(0.75..1.25);
+        self.timer = Timer::from_seconds(cooldown, TimerMode::Once);
+        angles
+    }
+}
+
+/// 从 `start` 开始均匀分布的 `count` 个方向
+fn spread_evenly(start: f32, count: u32) -> Vec<f32> {
+    (0..count)
+        .map(|i| start + TAU * i as f32 / count as f32)
+        .collect()
+}
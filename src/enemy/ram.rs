@@ -0,0 +1,112 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+
+use super::{formation::Formation, kind::EnemyKind};
+use crate::{
+    components::{Enemy, Player},
+    config::GameConfig,
+    GameSet, MaxEnemy, Playfield,
+};
+
+/// 撞击组件，敌人脱离阵型后朝玩家飞行
+#[derive(Component)]
+pub struct Ramming {
+    /// 飞行方向 (单位向量)
+    direction: Vec2,
+    speed: f32,
+}
+
+pub struct RamPlugin;
+
+impl Plugin for RamPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (ram_start_system, ram_movement_system)
+                .chain()
+                // 追踪玩家移动之后的位置
+                .after(crate::player::player_movable_system)
+                .before(super::enemy_movement_system)
+                .in_set(GameSet::Movement)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+/// 撞击开始系统，撞击型敌人进入窗口并接近玩家后脱离阵型
+fn ram_start_system(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    playfield: Res<Playfield>,
+    player_query: Query<&Transform, With<Player>>,
+    query: Query<(Entity, &Transform, &EnemyKind, &Formation), With<Enemy>>,
+) {
+    let Ok(player_tf) = player_query.get_single() else {
+        return;
+    };
+    let player = player_tf.translation.xy();
+    let (half_w, half_h) = (playfield.w / 2., playfield.h / 2.);
+
+    for (entity, tf, kind, formation) in query.iter() {
+        let position = tf.translation.xy();
+        let on_screen = position.x.abs() < half_w && position.y.abs() < half_h;
+        if !config.enemies.get(*kind).ram
+            || !on_screen
+            || position.distance(player) > config.collision.ram_range
+        {
+            continue;
+        }
+
+        commands
+            .entity(entity)
+            .remove::<Formation>()
+            .insert(Ramming {
+                direction: (player - position).normalize_or_zero(),
+                speed: formation.speed * config.collision.ram_speed_scale,
+            });
+    }
+}
+
+/// 撞击飞行系统
+///
+/// 敌人以有限的转向速度追踪玩家，错过玩家并飞出窗口后消失，不计分
+fn ram_movement_system(
+    mut commands: Commands,
+    fixed_time: Res<FixedTime>,
+    config: Res<GameConfig>,
+    playfield: Res<Playfield>,
+    mut max_enemy: ResMut<MaxEnemy>,
+    player_query: Query<&Transform, (With<Player>, Without<Ramming>)>,
+    mut query: Query<(Entity, &mut Transform, &mut Ramming)>,
+) {
+    let delta = fixed_time.period.as_secs_f32();
+    let player = player_query.get_single().ok().map(|tf| tf.translation.xy());
+    let max_turn = config.collision.ram_turn_rate.to_radians() * delta;
+
+    for (entity, mut tf, mut ramming) in query.iter_mut() {
+        let position = tf.translation.xy();
+        // 只在玩家位于前方时转向，错过后保持方向飞出窗口，玩家不存在时同样保持方向
+        if let Some(target) = player {
+            let desired = target - position;
+            if ramming.direction == Vec2::ZERO {
+                ramming.direction = desired.normalize_or_zero();
+            } else if ramming.direction.dot(desired) > 0. {
+                let turn = ramming
+                    .direction
+                    .angle_between(desired)
+                    .clamp(-max_turn, max_turn);
+                ramming.direction = Vec2::from_angle(turn).rotate(ramming.direction);
+            }
+        }
+
+        let position = position + ramming.direction * ramming.speed * delta;
+        (tf.translation.x, tf.translation.y) = (position.x, position.y);
+
+        // 飞出窗口
+        let margin = 100.;
+        if position.x.abs() > playfield.w / 2. + margin
+            || position.y.abs() > playfield.h / 2. + margin
+        {
+            commands.entity(entity).despawn();
+            max_enemy.0 = max_enemy.0.saturating_sub(1);
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use bevy::{
+    prelude::{FromWorld, Resource, World},
+    time::{Timer, TimerMode},
+};
+use serde::{Deserialize, Serialize};
+
+use super::kind::EnemyKind;
+use crate::config::GameConfig;
+
+/// 阵型类型，决定阵型的移动路径
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FormationKind {
+    /// 横向较宽的椭圆
+    Wide,
+    /// 接近圆形的轨道
+    Circle,
+    /// 纵向较高的椭圆
+    Tall,
+    /// 正弦波横扫
+    Sine,
+    /// 贝塞尔曲线往返
+    Bezier,
+    /// 闭合样条
+    Spline,
+    /// 8 字形
+    FigureEight,
+    /// 俯冲
+    DiveBomb,
+    /// 网格行进
+    Grid,
+}
+
+impl FormationKind {
+    /// 全部阵型类型
+    pub const ALL: [FormationKind; 9] = [
+        FormationKind::Wide,
+        FormationKind::Circle,
+        FormationKind::Tall,
+        FormationKind::Sine,
+        FormationKind::Bezier,
+        FormationKind::Spline,
+        FormationKind::FigureEight,
+        FormationKind::DiveBomb,
+        FormationKind::Grid,
+    ];
+
+    /// 椭圆轨道半径的取值范围 ((x 最小, x 最大), (y 最小, y 最大))，其他类型不使用
+    pub fn radius_range(&self) -> ((f32, f32), (f32, f32)) {
+        match self {
+            FormationKind::Wide => ((80., 150.), (100., 100.)),
+            FormationKind::Tall => ((60., 90.), (120., 160.)),
+            _ => ((90., 120.), (90., 120.)),
+        }
+    }
+}
+
+/// 波次定义
+#[derive(Clone, Debug)]
+pub struct Wave {
+    /// 本波敌人总数
+    pub enemies: u32,
+    /// 同时存在的最大敌人数量
+    pub max_alive: u32,
+    /// 每个阵型的敌人数量
+    pub formation_members: u32,
+    /// 可选的阵型类型，每个阵型随机选择一种
+    pub formations: &'static [FormationKind],
+    /// 敌人速度
+    pub speed: f32,
+    /// 敌人平均每秒攻击次数
+    pub fire_rate: f64,
+    /// 敌人类型及出现权重
+    pub kinds: &'static [(EnemyKind, u32)],
+}
+
+/// 波次模板，数值以配置中第一波的数值为基准
+#[derive(Clone, Copy, Debug)]
+pub struct WaveTemplate {
+    /// 本波敌人总数
+    pub enemies: u32,
+    /// 同时存在的最大敌人数量，在配置的基础上增加
+    pub extra_alive: u32,
+    /// 每个阵型的敌人数量，在配置的基础上增加
+    pub extra_members: u32,
+    /// 可选的阵型类型，每个阵型随机选择一种
+    pub formations: &'static [FormationKind],
+    /// 敌人速度相对基础速度的倍数
+    pub speed_scale: f32,
+    /// 攻击频率相对配置的倍数
+    pub fire_scale: f64,
+    /// 敌人类型及出现权重
+    pub kinds: &'static [(EnemyKind, u32)],
+}
+
+/// 预设波次，超过预设数量后在最后一波的基础上逐波加强
+pub const WAVES: [WaveTemplate; 5] = [
+    WaveTemplate {
+        enemies: 6,
+        extra_alive: 0,
+        extra_members: 0,
+        formations: &[FormationKind::Wide],
+        speed_scale: 1.,
+        fire_scale: 1.,
+        kinds: &[(EnemyKind::Scout, 1)],
+    },
+    WaveTemplate {
+        enemies: 8,
+        extra_alive: 1,
+        extra_members: 0,
+        formations: &[FormationKind::Circle, FormationKind::Sine],
+        speed_scale: 1.,
+        fire_scale: 1.25,
+        kinds: &[(EnemyKind::Scout, 3), (EnemyKind::Gunner, 1)],
+    },
+    WaveTemplate {
+        enemies: 10,
+        extra_alive: 2,
+        extra_members: 1,
+        formations: &[
+            FormationKind::Tall,
+            FormationKind::FigureEight,
+            FormationKind::Bezier,
+        ],
+        speed_scale: 1.1,
+        fire_scale: 1.5,
+        kinds: &[
+            (EnemyKind::Scout, 2),
+            (EnemyKind::Gunner, 2),
+            (EnemyKind::Kamikaze, 1),
+        ],
+    },
+    WaveTemplate {
+        enemies: 12,
+        extra_alive: 2,
+        extra_members: 1,
+        formations: &[
+            FormationKind::Wide,
+            FormationKind::Spline,
+            FormationKind::DiveBomb,
+            FormationKind::Grid,
+        ],
+        speed_scale: 1.2,
+        fire_scale: 1.75,
+        kinds: &[
+            (EnemyKind::Scout, 2),
+            (EnemyKind::Gunner, 2),
+            (EnemyKind::Tank, 1),
+            (EnemyKind::Kamikaze, 1),
+        ],
+    },
+    WaveTemplate {
+        enemies: 15,
+        extra_alive: 3,
+        extra_members: 2,
+        formations: &[
+            FormationKind::Circle,
+            FormationKind::Spline,
+            FormationKind::FigureEight,
+            FormationKind::DiveBomb,
+            FormationKind::Grid,
+        ],
+        speed_scale: 1.3,
+        fire_scale: 2.,
+        kinds: &[
+            (EnemyKind::Scout, 1),
+            (EnemyKind::Gunner, 2),
+            (EnemyKind::Tank, 2),
+            (EnemyKind::Kamikaze, 2),
+        ],
+    },
+];
+
+impl WaveTemplate {
+    /// 根据配置生成波次定义
+    pub fn build(&self, config: &GameConfig) -> Wave {
+        Wave {
+            enemies: self.enemies,
+            max_alive: config.max_enemy + self.extra_alive,
+            formation_members: config.formation_member_max + self.extra_members,
+            formations: self.formations,
+            speed: config.base_speed * self.speed_scale,
+            fire_rate: config.enemy_fire_rate * self.fire_scale,
+            kinds: self.kinds,
+        }
+    }
+}
+
+impl Wave {
+    /// 获取第 `number` 波 (从 1 开始) 的定义
+    pub fn get(number: u32, config: &GameConfig) -> Self {
+        let index = number.max(1) as usize - 1;
+        if let Some(template) = WAVES.get(index) {
+            return template.build(config);
+        }
+
+        // 超出预设的波次：敌人数量、速度、攻击频率逐波增加，阵型从全部类型中选择
+        let extra = (index + 1 - WAVES.len()) as u32;
+        let last = WAVES[WAVES.len() - 1].build(config);
+        Wave {
+            enemies: last.enemies + extra * 2,
+            max_alive: last.max_alive + extra / 2,
+            formation_members: last.formation_members,
+            formations: &FormationKind::ALL,
+            speed: last.speed * (1. + 0.05 * extra as f32).min(1.5),
+            fire_rate: last.fire_rate * (1. + 0.1 * extra as f64),
+            kinds: last.kinds,
+        }
+    }
+}
+
+/// 波次管理资源
+#[derive(Resource)]
+pub struct WaveManager {
+    /// 当前波次定义
+    wave: Wave,
+    /// 本波已生成的敌人数量
+    spawned: u32,
+    /// 波次横幅计时器，横幅展示期间不生成敌人
+    banner: Option<Timer>,
+    /// 本波末尾是否还有 Boss 等待出现
+    boss_pending: bool,
+}
+
+impl FromWorld for WaveManager {
+    fn from_world(world: &mut World) -> Self {
+        let config = world.get_resource_or_insert_with(GameConfig::default);
+        Self::start(1, &config)
+    }
+}
+
+impl WaveManager {
+    /// 开始第 `number` 波
+    pub fn start(number: u32, config: &GameConfig) -> Self {
+        Self {
+            wave: Wave::get(number, config),
+            spawned: 0,
+            banner: Some(Timer::from_seconds(
+                config.wave_banner_seconds,
+                TimerMode::Once,
+            )),
+            boss_pending: config.boss.appears_in(number),
+        }
+    }
+
+    /// 获取当前波次定义
+    pub fn wave(&self) -> &Wave {
+        &self.wave
+    }
+
+    /// 获取波次横幅计时器
+    pub fn banner_mut(&mut self) -> Option<&mut Timer> {
+        self.banner.as_mut()
+    }
+
+    /// 结束横幅展示，开始生成敌人
+    pub fn finish_banner(&mut self) {
+        self.banner = None;
+    }
+
+    /// 当前存活 `alive` 个敌人时能否生成新的敌人
+    pub fn can_spawn(&self, alive: u32) -> bool {
+        self.banner.is_none() && self.spawned < self.wave.enemies && alive < self.wave.max_alive
+    }
+
+    /// 记录生成了一个敌人
+    pub fn spawned(&mut self) {
+        self.spawned += 1;
+    }
+
+    /// 记录本波敌人已经全部生成
+    pub fn spawned_all(&mut self) {
+        self.spawned = self.spawned.max(self.wave.enemies);
+    }
+
+    /// 本波普通敌人已全部被消灭，等待 Boss 出现
+    pub fn boss_ready(&self, alive: u32) -> bool {
+        self.boss_pending
+            && self.banner.is_none()
+            && self.spawned >= self.wave.enemies
+            && alive == 0
+    }
+
+    /// 记录 Boss 已经出现
+    pub fn boss_spawned(&mut self) {
+        self.boss_pending = false;
+    }
+
+    /// 本波敌人 (包括 Boss) 是否已全部生成并被消灭
+    pub fn cleared(&self, alive: u32) -> bool {
+        !self.boss_pending
+            && self.banner.is_none()
+            && self.spawned >= self.wave.enemies
+            && alive == 0
+    }
+}
@@ -0,0 +1,54 @@
+use bevy::prelude::{Entity, Vec3};
+
+use crate::collision::Layers;
+
+/// 伤害事件
+///
+/// 碰撞处理系统发送，由 `damage_system` 扣除目标的生命值
+#[derive(Clone, Copy, Debug)]
+pub struct DamageEvent {
+    /// 受到伤害的实体
+    pub target: Entity,
+    /// 伤害值
+    pub amount: u32,
+}
+
+/// 敌人被消灭事件
+#[derive(Clone, Copy, Debug)]
+pub struct EnemyKilledEvent {
+    /// 得分 (未计算得分倍数)
+    pub score: u32,
+    /// 被消灭的位置
+    pub position: Vec3,
+}
+
+/// 玩家被摧毁事件
+#[derive(Clone, Copy, Debug)]
+pub struct PlayerKilledEvent {
+    /// 被摧毁的位置
+    pub position: Vec3,
+}
+
+/// 碰撞事件
+///
+/// 由 `collision_detection_system` 对每一对发生碰撞的实体发送一次，双方的先后顺序不固定
+#[derive(Clone, Copy, Debug)]
+pub struct CollisionEvent {
+    pub a: Entity,
+    pub a_layer: Layers,
+    pub b: Entity,
+    pub b_layer: Layers,
+}
+
+impl CollisionEvent {
+    /// 碰撞双方分别位于 `first` 与 `second` 层时，按该顺序返回双方的实体
+    pub fn between(&self, first: Layers, second: Layers) -> Option<(Entity, Entity)> {
+        if self.a_layer.intersects(first) && self.b_layer.intersects(second) {
+            Some((self.a, self.b))
+        } else if self.b_layer.intersects(first) && self.a_layer.intersects(second) {
+            Some((self.b, self.a))
+        } else {
+            None
+        }
+    }
+}
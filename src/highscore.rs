@@ -0,0 +1,300 @@
+use std::{
+    cmp::Reverse,
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::Resource;
+
+/// 排行榜最大记录数
+pub const HIGH_SCORE_MAX: usize = 10;
+/// 玩家名字长度
+pub const HIGH_SCORE_NAME_LEN: usize = 3;
+
+/// 排行榜记录
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HighScoreEntry {
+    /// 玩家名字，三个大写字母
+    pub name: String,
+    /// 得分
+    pub score: u32,
+    /// 到达的波次
+    pub wave: u32,
+    /// 日期 (YYYY-MM-DD)
+    pub date: String,
+}
+
+/// 排行榜资源
+///
+/// 每行一条记录，字段以制表符分隔：名字、得分、波次、日期
+#[derive(Resource, Default)]
+pub struct HighScores {
+    entries: Vec<HighScoreEntry>,
+    /// 保存路径，为空时只保存在内存中
+    path: Option<PathBuf>,
+}
+
+impl HighScores {
+    /// 默认保存路径：用户数据目录下的 invaders/highscores.txt
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("invaders").join("highscores.txt"))
+    }
+
+    /// 从文件读取排行榜，文件不存在时为空榜，无法解析的行会被忽略
+    pub fn load(path: Option<PathBuf>) -> io::Result<Self> {
+        let mut high_scores = Self {
+            entries: Vec::new(),
+            path,
+        };
+
+        if let Some(path) = &high_scores.path {
+            match fs::read_to_string(path) {
+                Ok(content) => {
+                    high_scores.entries = content.lines().filter_map(parse_entry).collect();
+                    high_scores
+                        .entries
+                        .sort_by_key(|entry| Reverse(entry.score));
+                    high_scores.entries.truncate(HIGH_SCORE_MAX);
+                }
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(high_scores)
+    }
+
+    /// 写入文件
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut file = fs::File::create(path)?;
+        for entry in &self.entries {
+            writeln!(
+                file,
+                "{}\t{}\t{}\t{}",
+                entry.name, entry.score, entry.wave, entry.date
+            )?;
+        }
+        Ok(())
+    }
+
+    /// 获取排行榜记录，按得分从高到低排列
+    pub fn entries(&self) -> &[HighScoreEntry] {
+        &self.entries
+    }
+
+    /// 得分是否可以进入排行榜
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.entries.len() < HIGH_SCORE_MAX
+                || self.entries.iter().any(|entry| score > entry.score))
+    }
+
+    /// 添加记录，同分时先到者排名靠前
+    pub fn insert(&mut self, entry: HighScoreEntry) {
+        let index = self
+            .entries
+            .iter()
+            .position(|other| entry.score > other.score)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(index, entry);
+        self.entries.truncate(HIGH_SCORE_MAX);
+    }
+}
+
+/// 解析一行记录
+fn parse_entry(line: &str) -> Option<HighScoreEntry> {
+    let mut fields = line.split('\t');
+    let entry = HighScoreEntry {
+        name: fields.next()?.to_owned(),
+        score: fields.next()?.parse().ok()?,
+        wave: fields.next()?.parse().ok()?,
+        date: fields.next()?.to_owned(),
+    };
+    Some(entry)
+}
+
+/// 当前日期 (UTC)，格式 YYYY-MM-DD
+pub fn today() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// 将 1970-01-01 起的天数转换为公历日期
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// 名字输入状态
+#[derive(Resource)]
+pub struct NameEntry {
+    /// 已输入的字母
+    pub letters: [u8; HIGH_SCORE_NAME_LEN],
+    /// 当前光标位置
+    pub cursor: usize,
+}
+
+impl Default for NameEntry {
+    fn default() -> Self {
+        Self {
+            letters: [b'A'; HIGH_SCORE_NAME_LEN],
+            cursor: 0,
+        }
+    }
+}
+
+impl NameEntry {
+    /// 切换当前字母，`step` 为 1 或 -1，在 A-Z 之间循环
+    pub fn cycle_letter(&mut self, step: i8) {
+        let letter = &mut self.letters[self.cursor];
+        let index = (*letter - b'A') as i8 + step;
+        *letter = b'A' + index.rem_euclid(26) as u8;
+    }
+
+    /// 移动光标，`step` 为 1 或 -1
+    pub fn move_cursor(&mut self, step: i8) {
+        let cursor = self.cursor as i8 + step;
+        self.cursor = cursor.clamp(0, HIGH_SCORE_NAME_LEN as i8 - 1) as usize;
+    }
+
+    /// 获取输入的名字
+    pub fn name(&self) -> String {
+        String::from_utf8_lossy(&self.letters).into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, score: u32) -> HighScoreEntry {
+        HighScoreEntry {
+            name: name.to_owned(),
+            score,
+            wave: 1,
+            date: "2024-01-01".to_owned(),
+        }
+    }
+
+    /// 得分从 100 到 1000 的满榜
+    fn full_table() -> HighScores {
+        let mut high_scores = HighScores::default();
+        for i in 1..=HIGH_SCORE_MAX as u32 {
+            high_scores.insert(entry("AAA", i * 100));
+        }
+        high_scores
+    }
+
+    #[test]
+    fn ties_at_the_cutoff() {
+        let mut high_scores = full_table();
+        // 与最后一名同分不能进榜
+        assert!(!high_scores.qualifies(100));
+        assert!(high_scores.qualifies(101));
+
+        // 同分时先到者排名靠前
+        high_scores.insert(entry("BBB", 500));
+        let names: Vec<_> = high_scores
+            .entries()
+            .iter()
+            .filter(|entry| entry.score == 500)
+            .map(|entry| entry.name.as_str())
+            .collect();
+        assert_eq!(names, ["AAA", "BBB"]);
+    }
+
+    #[test]
+    fn full_table_is_truncated() {
+        let mut high_scores = full_table();
+        assert!(!high_scores.qualifies(50));
+        assert!(!high_scores.qualifies(0));
+        assert!(HighScores::default().qualifies(1));
+        assert!(!HighScores::default().qualifies(0));
+
+        high_scores.insert(entry("BBB", 50));
+        assert_eq!(high_scores.entries().len(), HIGH_SCORE_MAX);
+        assert!(high_scores
+            .entries()
+            .iter()
+            .all(|entry| entry.name == "AAA"));
+
+        high_scores.insert(entry("CCC", 2000));
+        let entries = high_scores.entries();
+        assert_eq!(entries.len(), HIGH_SCORE_MAX);
+        assert_eq!(entries[0].name, "CCC");
+        assert_eq!(entries.last().unwrap().score, 200);
+    }
+
+    #[test]
+    fn corrupt_lines_are_skipped() {
+        assert_eq!(
+            parse_entry("ABC\t1200\t3\t2024-02-29"),
+            Some(HighScoreEntry {
+                name: "ABC".to_owned(),
+                score: 1200,
+                wave: 3,
+                date: "2024-02-29".to_owned(),
+            })
+        );
+        for line in [
+            "",
+            "ABC",
+            "ABC\t12x\t3\t2024-02-29",
+            "ABC\t1200\t-1\t2024-02-29",
+            "ABC\t1200\t3",
+        ] {
+            assert_eq!(parse_entry(line), None, "{line:?}");
+        }
+
+        let path =
+            std::env::temp_dir().join(format!("invaders-corrupt-lines-{}.txt", std::process::id()));
+        fs::write(
+            &path,
+            "AAA\t100\t1\t2024-01-01\ngarbage\nBBB\t300\t2\t2024-01-02\nCCC\tlots\t3\t2024-01-03\n",
+        )
+        .unwrap();
+        let high_scores = HighScores::load(Some(path.clone())).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let names: Vec<_> = high_scores
+            .entries()
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect();
+        assert_eq!(names, ["BBB", "AAA"]);
+    }
+
+    #[test]
+    fn civil_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(11_017), (2000, 3, 1));
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+        // 2100 年与 1900 年不是闰年
+        assert_eq!(civil_from_days(47_540), (2100, 2, 28));
+        assert_eq!(civil_from_days(47_541), (2100, 3, 1));
+        assert_eq!(civil_from_days(-25_508), (1900, 3, 1));
+    }
+}
@@ -0,0 +1,899 @@
+use action::ActionPlugin;
+use bevy::{
+    input::InputPlugin,
+    prelude::*,
+    render::camera::ScalingMode,
+    sprite::Anchor,
+    utils::{HashMap, HashSet},
+    window::{PrimaryWindow, WindowResized},
+};
+use bunker::BunkerPlugin;
+use collision::{shape::ColliderShapes, CollisionPlugin, Layers};
+use components::*;
+
+use config::GameConfig;
+use constants::{HIT_FLASH_COLOR, SHIELD_BAR_SIZE};
+use enemy::{
+    boss::{Boss, HitZone},
+    kind::EnemyKind,
+    EnemyPlugin,
+};
+use events::{CollisionEvent, DamageEvent, EnemyKilledEvent, PlayerKilledEvent};
+use highscore::HighScores;
+use player::PlayerPlugin;
+use powerup::{ActivePowerUps, PowerUpKind, PowerUpPlugin};
+use rand::Rng;
+use resource::{
+    GameAudio, GameClock, GameData, GameRng, GameState, GameTextures, MaxEnemy, PlayerState,
+    Playfield, WinSize,
+};
+use state::StatePlugin;
+use std::path::PathBuf;
+use weapon::{WeaponKind, WeaponPlugin};
+
+pub mod action;
+pub mod bunker;
+pub mod collision;
+pub mod components;
+pub mod config;
+pub mod constants;
+pub mod enemy;
+pub mod events;
+pub mod highscore;
+pub mod player;
+pub mod powerup;
+pub mod replay;
+pub mod resource;
+pub mod state;
+pub mod weapon;
+
+/// 游戏插件
+///
+/// 包含游戏状态、玩家、敌人、状态管理插件以及碰撞、爆炸、记分等系统。
+/// 窗口模式需要在 `DefaultPlugins` 之后添加，
+/// 无窗口模式 (`InvadersPlugin::headless()`) 只需要 `MinimalPlugins`。
+pub struct InvadersPlugin {
+    /// 无窗口模式：不读取窗口与 AssetServer，使用固定的 WinSize 与空的图片、声音句柄
+    pub headless: bool,
+    /// 游戏配置，其中的固定步长决定移动、攻击、碰撞等逻辑的运行频率，与渲染帧率无关
+    pub config: GameConfig,
+    /// 随机数种子，为空时随机生成
+    pub seed: Option<u64>,
+    /// 排行榜文件路径，为空时排行榜只保存在内存中
+    pub high_score_path: Option<PathBuf>,
+}
+
+impl Default for InvadersPlugin {
+    fn default() -> Self {
+        Self {
+            headless: false,
+            config: GameConfig::default(),
+            seed: None,
+            high_score_path: HighScores::default_path(),
+        }
+    }
+}
+
+impl InvadersPlugin {
+    /// 无窗口模式，用于集成测试与机器人
+    pub fn headless() -> Self {
+        Self {
+            headless: true,
+            high_score_path: None,
+            ..Default::default()
+        }
+    }
+}
+
+/// 固定步长调度 (`CoreSchedule::FixedUpdate`) 中游戏逻辑的执行顺序
+///
+/// 各集合按声明顺序依次执行，并且只在 `GameState::InGame` 状态下运行，
+/// 保证同样的输入在不同帧率下得到相同的结果。
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameSet {
+    /// 生成玩家、敌人
+    Spawn,
+    /// 发射激光
+    Fire,
+    /// 移动
+    Movement,
+    /// 碰撞判定
+    Collision,
+}
+
+impl Plugin for InvadersPlugin {
+    fn build(&self, app: &mut App) {
+        // 代码中构造的配置不经过 GameConfig::load，在这里统一校验
+        if let Err(err) = self.config.validate() {
+            panic!("{err}");
+        }
+
+        if self.headless {
+            // MinimalPlugins 不包含键盘、手柄输入与声音，这里补齐系统依赖的资源
+            if !app.world.contains_resource::<Input<KeyCode>>() {
+                app.add_plugin(InputPlugin);
+            }
+            app.init_resource::<Audio>()
+                .add_startup_system(headless_setup_system)
+                .add_system(headless_audio_system.in_base_set(CoreSet::Last));
+        } else {
+            app.add_startup_system(setup_system)
+                .add_system(window_resize_system);
+        }
+
+        // 随机数种子，记录到日志中以便复现
+        let seed = self.seed.unwrap_or_else(|| rand::thread_rng().gen());
+        info!("game seed: {seed}");
+        app.insert_resource(GameRng::new(seed))
+            .insert_resource(GameData::new(self.config.player_lives))
+            .insert_resource(Playfield {
+                w: self.config.window_size.0,
+                h: self.config.window_size.1,
+            })
+            .insert_resource(self.config.clone());
+
+        // 排行榜
+        let high_scores = HighScores::load(self.high_score_path.clone()).unwrap_or_else(|err| {
+            warn!("failed to load high scores, using an in-memory table: {err}");
+            HighScores::default()
+        });
+        app.insert_resource(high_scores);
+
+        // 碰撞形状，像素遮罩由图片的透明通道生成
+        app.insert_resource(ColliderShapes::load(&self.config));
+
+        // 固定步长调度，FixedUpdate 在每帧中根据实际流逝的时间运行 0 次或多次
+        app.insert_resource(FixedTime::new_from_secs(self.config.time_step))
+            .init_resource::<GameClock>()
+            .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+                schedule.configure_sets(
+                    (
+                        GameSet::Spawn,
+                        GameSet::Fire,
+                        GameSet::Movement,
+                        GameSet::Collision,
+                    )
+                        .chain(),
+                );
+                for set in [
+                    GameSet::Spawn,
+                    GameSet::Fire,
+                    GameSet::Movement,
+                    GameSet::Collision,
+                ] {
+                    schedule.configure_set(set.run_if(in_state(GameState::InGame)));
+                }
+            })
+            .add_system(
+                game_clock_system
+                    .before(GameSet::Spawn)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            );
+
+        // add_startup_system 启动生命周期时只运行一次 ，
+        // add_system 每帧都会被调用方法
+        app.add_state::<GameState>()
+            .add_plugin(ActionPlugin)
+            .add_plugin(PlayerPlugin)
+            .add_plugin(EnemyPlugin)
+            .add_plugin(StatePlugin)
+            .add_plugin(PowerUpPlugin)
+            .add_plugin(WeaponPlugin)
+            .add_plugin(BunkerPlugin)
+            .add_plugin(CollisionPlugin)
+            // 固定步长下执行的函数
+            .add_system(
+                laser_movable_system
+                    .in_set(GameSet::Movement)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_event::<DamageEvent>()
+            .add_event::<EnemyKilledEvent>()
+            .add_event::<PlayerKilledEvent>()
+            .add_systems(
+                (
+                    player_laser_hit_enemy_system,
+                    enemy_laser_hit_player_system,
+                    player_enemy_collision_system,
+                    damage_system,
+                )
+                    .chain()
+                    .in_set(GameSet::Collision)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            // 响应消灭事件的记分、音效与爆炸效果
+            .add_systems(
+                (
+                    score_system,
+                    explosion_sound_system,
+                    explosion_effect_system,
+                )
+                    .after(damage_system)
+                    .in_set(GameSet::Collision)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            // 爆炸动画在游戏结束画面中继续播放
+            .add_systems(
+                (explosion_to_spawn_system, explosion_animation_system)
+                    .distributive_run_if(explosion_criteria),
+            )
+            .add_system(hit_flash_system)
+            // 分数与护盾在所有状态下都需要显示
+            .add_system(hud_anchor_system)
+            .add_system(score_display_update_system)
+            .add_system(shield_bar_update_system);
+    }
+}
+
+/// 资源加载
+pub fn setup_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<GameConfig>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    playfield: Res<Playfield>,
+    mut windows: Query<&mut Window>,
+) {
+    // 创建2d镜头，按窗口大小缩放使整个游戏区域可见
+    let mut camera = Camera2dBundle::default();
+    camera.projection.scaling_mode = ScalingMode::AutoMin {
+        min_width: playfield.w,
+        min_height: playfield.h,
+    };
+    commands.spawn(camera);
+
+    // 获取当前窗口
+    let window = windows.single_mut();
+    let win_size = WinSize {
+        w: window.width(),
+        h: window.height(),
+    };
+
+    // 创建爆炸动画
+    let sprites = &config.sprites;
+    let texture_handle = asset_server.load(sprites.explosion.path.as_str());
+    let texture_atlas = TextureAtlas::from_grid(
+        texture_handle,
+        Vec2::from(sprites.explosion.size),
+        sprites.explosion.columns,
+        sprites.explosion.rows,
+        None,
+        None,
+    );
+    let explosion = texture_atlases.add(texture_atlas);
+
+    // 添加 GameTextures
+    let game_texture = GameTextures {
+        background: asset_server.load(sprites.background.as_str()),
+        player: asset_server.load(sprites.player.path.as_str()),
+        enemies: EnemyKind::ALL
+            .into_iter()
+            .map(|kind| {
+                let path = config.enemies.get(kind).sprite.path.as_str();
+                (kind, asset_server.load(path))
+            })
+            .collect(),
+        weapons: WeaponKind::ALL
+            .into_iter()
+            .map(|kind| {
+                let path = config.weapons.get(kind).sprite.path.as_str();
+                (kind, asset_server.load(path))
+            })
+            .collect(),
+        boss: asset_server.load(config.boss.sprite.path.as_str()),
+        font: asset_server.load(config.font.as_str()),
+        explosion,
+    };
+
+    // 声音资源引入
+    let audios = &config.audios;
+    let game_audio = GameAudio {
+        player_laser: asset_server.load(audios.player_laser.as_str()),
+        player_explosion: asset_server.load(audios.player_explosion.as_str()),
+        enemy_explosion: asset_server.load(audios.enemy_explosion.as_str()),
+    };
+
+    spawn_playfield(
+        &mut commands,
+        win_size,
+        *playfield,
+        game_texture,
+        game_audio,
+    );
+}
+
+/// 无窗口模式下的资源加载，使用固定窗口大小与空的图片、声音句柄
+pub fn headless_setup_system(mut commands: Commands, playfield: Res<Playfield>) {
+    let win_size = WinSize {
+        w: playfield.w,
+        h: playfield.h,
+    };
+
+    spawn_playfield(
+        &mut commands,
+        win_size,
+        *playfield,
+        GameTextures::default(),
+        GameAudio::default(),
+    );
+}
+
+/// 无窗口模式下没有 AudioPlugin 消费播放队列，每帧清空防止无限增长
+fn headless_audio_system(mut audio: ResMut<Audio>) {
+    *audio = Audio::default();
+}
+
+/// 生成背景、黑边、分数控件并添加游戏资源
+fn spawn_playfield(
+    commands: &mut Commands,
+    win_size: WinSize,
+    playfield: Playfield,
+    game_texture: GameTextures,
+    game_audio: GameAudio,
+) {
+    // 背景图片
+    commands.spawn(SpriteBundle {
+        texture: game_texture.background.clone(),
+        sprite: Sprite {
+            custom_size: Some(Vec2 {
+                x: playfield.w,
+                y: playfield.h,
+            }),
+            ..Default::default()
+        },
+        transform: Transform::from_scale(Vec3::new(1.5, 1.5, 0.0)),
+        ..Default::default()
+    });
+
+    // 黑边，遮挡窗口比例与游戏区域不同时多出的部分，位于游戏实体之上、HUD 之下
+    let (w, h) = (playfield.w, playfield.h);
+    let extent = w.max(h) * 4.;
+    let side = Vec2::new(extent, h + extent * 2.);
+    let cap = Vec2::new(w, extent);
+    for (x, y, size) in [
+        (-(w + extent) / 2., 0., side),
+        ((w + extent) / 2., 0., side),
+        (0., (h + extent) / 2., cap),
+        (0., -(h + extent) / 2., cap),
+    ] {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::BLACK,
+                    custom_size: Some(size),
+                    ..Default::default()
+                },
+                transform: Transform::from_xyz(x, y, 10.5),
+                ..Default::default()
+            },
+            Letterbox,
+        ));
+    }
+
+    // 字体引入
+    let font = game_texture.font.clone();
+    let text_style = TextStyle {
+        font: font.clone(),
+        font_size: 24.,
+        color: Color::ANTIQUE_WHITE,
+    };
+    let text_alignment = TextAlignment::Center;
+
+    // 分数展示控件
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section("", text_style).with_alignment(text_alignment),
+            transform: Transform::from_xyz(0., 0., 11.),
+            ..Default::default()
+        },
+        HudAnchor::new(Vec2::Y, Vec2::new(0., -20.)),
+        DisplayScore,
+    ));
+
+    // 护盾条，填充部分以左端为锚点按剩余护盾缩放宽度
+    let (bar_w, bar_h) = SHIELD_BAR_SIZE;
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(1., 1., 1., 0.2),
+                custom_size: Some(Vec2::new(bar_w, bar_h)),
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(0., 0., 11.),
+            ..Default::default()
+        },
+        HudAnchor::new(Vec2::Y, Vec2::new(0., -45.)),
+    ));
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::CYAN,
+                custom_size: Some(Vec2::new(0., bar_h)),
+                anchor: Anchor::CenterLeft,
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(0., 0., 12.),
+            ..Default::default()
+        },
+        HudAnchor::new(Vec2::Y, Vec2::new(-bar_w / 2., -45.)),
+        ShieldBar,
+    ));
+
+    commands.insert_resource(win_size);
+    commands.insert_resource(game_audio);
+    commands.insert_resource(game_texture);
+    commands.insert_resource(MaxEnemy(0));
+}
+
+/// 窗口缩放系统，保持 WinSize 与主窗口大小一致
+pub fn window_resize_system(
+    mut resize_events: EventReader<WindowResized>,
+    primary_query: Query<(), With<PrimaryWindow>>,
+    mut win_size: ResMut<WinSize>,
+) {
+    for event in resize_events.iter() {
+        if primary_query.contains(event.window) {
+            win_size.w = event.width;
+            win_size.h = event.height;
+        }
+    }
+}
+
+/// HUD 定位系统
+///
+/// 新生成的 HUD 控件或窗口大小改变时，按锚点在窗口可见区域中重新计算位置
+pub fn hud_anchor_system(
+    win_size: Res<WinSize>,
+    playfield: Res<Playfield>,
+    mut query: Query<(Ref<HudAnchor>, &mut Transform)>,
+) {
+    let (w, h) = playfield.visible_size(&win_size);
+    let half = Vec2::new(w, h) / 2.;
+    for (hud, mut tf) in query.iter_mut() {
+        if !win_size.is_changed() && !hud.is_added() {
+            continue;
+        }
+        let position = hud.anchor * half + hud.offset;
+        tf.translation.x = position.x;
+        tf.translation.y = position.y;
+    }
+}
+
+/// 游戏时钟系统，每个固定步长推进一次
+pub fn game_clock_system(fixed_time: Res<FixedTime>, mut clock: ResMut<GameClock>) {
+    clock.tick(fixed_time.period);
+}
+
+/// 激光与道具移动系统
+#[allow(clippy::type_complexity)]
+pub fn laser_movable_system(
+    mut commands: Commands,
+    fixed_time: Res<FixedTime>,
+    config: Res<GameConfig>,
+    playfield: Res<Playfield>,
+    mut query: Query<
+        (Entity, &Velocity, &mut Transform, &Movable),
+        Or<(With<Laser>, With<PowerUpKind>)>,
+    >,
+) {
+    let delta = fixed_time.period.as_secs_f32();
+    let speed = config.base_speed;
+    for (entity, velocity, mut transform, movable) in query.iter_mut() {
+        // 移动位置
+        let translation = &mut transform.translation;
+        translation.x += velocity.x * speed * delta;
+        translation.y += velocity.y * speed * delta;
+
+        // 自动销毁
+        if movable.auto_despawn {
+            const MARGIN: f32 = 200.;
+            if translation.y > playfield.h / 2. + MARGIN
+                || translation.y < -playfield.h / 2. - MARGIN
+                || translation.x > playfield.w / 2. + MARGIN
+                || translation.x < -playfield.w / 2. - MARGIN
+            {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+/// 敌人激光攻击玩家判定系统
+pub fn enemy_laser_hit_player_system(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut laser_query: Query<(&Damage, Option<&mut Piercing>), With<Laser>>,
+    player_query: Query<&Health, With<Player>>,
+) {
+    // 本步长内剩余的护盾值，耗尽后其余激光不再命中
+    let mut remaining = None;
+
+    for event in collision_events.iter() {
+        let Some((laser, player_entity)) = event.between(Layers::ENEMY_PROJECTILE, Layers::PLAYER)
+        else {
+            continue;
+        };
+        let Ok(health) = player_query.get(player_entity) else {
+            continue;
+        };
+        let remaining = remaining.get_or_insert(health.current);
+        if *remaining == 0 {
+            continue;
+        }
+        let Ok((damage, mut piercing)) = laser_query.get_mut(laser) else {
+            continue;
+        };
+        // 穿透弹道只命中同一目标一次
+        if let Some(piercing) = &piercing {
+            if piercing.has_hit(player_entity) {
+                continue;
+            }
+        }
+
+        *remaining = remaining.saturating_sub(damage.0);
+        // 销毁激光，穿透弹道继续飞行
+        let pierced = piercing
+            .as_mut()
+            .is_some_and(|piercing| piercing.pierce(player_entity));
+        if !pierced {
+            commands.entity(laser).despawn();
+        }
+        damage_events.send(DamageEvent {
+            target: player_entity,
+            amount: damage.0,
+        });
+    }
+}
+
+/// 机体碰撞判定系统
+///
+/// 玩家与敌人的机体相撞时双方都受到伤害，撞到 Boss 的受击区域时伤害结算到 Boss 本身。
+/// 同一个敌人在冷却时间内不会再次撞击，避免重叠期间每个步长都结算伤害
+pub fn player_enemy_collision_system(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+    config: Res<GameConfig>,
+    clock: Res<GameClock>,
+    enemy_query: Query<(Option<&EnemyKind>, Option<&HitZone>), With<Enemy>>,
+    cooldown_query: Query<&ContactCooldown>,
+) {
+    let now = clock.elapsed_seconds_f64();
+    let collision_config = &config.collision;
+    // 本步长内已经相撞的目标，Boss 的多个受击区域只结算一次
+    let mut hit = HashSet::new();
+
+    for event in collision_events.iter() {
+        let Some((player_entity, enemy_entity)) = event.between(Layers::PLAYER, Layers::ENEMY)
+        else {
+            continue;
+        };
+        let Ok((kind, zone)) = enemy_query.get(enemy_entity) else {
+            continue;
+        };
+        let target = zone.map_or(enemy_entity, |zone| zone.owner);
+        if hit.contains(&target)
+            || cooldown_query
+                .get(target)
+                .is_ok_and(|cooldown| now < cooldown.0)
+        {
+            continue;
+        }
+
+        let contact_damage = match kind {
+            Some(kind) => config.enemies.get(*kind).contact_damage,
+            None => config.boss.contact_damage,
+        };
+        let multiplier = zone.map_or(1, |zone| zone.multiplier);
+        damage_events.send(DamageEvent {
+            target: player_entity,
+            amount: contact_damage,
+        });
+        damage_events.send(DamageEvent {
+            target,
+            amount: collision_config.player_damage * multiplier,
+        });
+        commands.entity(target).insert(ContactCooldown(
+            now + collision_config.contact_cooldown as f64,
+        ));
+        hit.insert(target);
+    }
+}
+
+/// 玩家攻击敌人判定系统
+pub fn player_laser_hit_enemy_system(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut laser_query: Query<(&Damage, Option<&mut Piercing>), With<Laser>>,
+    enemy_query: Query<Option<&HitZone>, With<Enemy>>,
+    health_query: Query<&Health>,
+) {
+    // 本步长内敌人剩余的生命值，生命值耗尽的敌人不再被其余激光命中
+    let mut remaining: HashMap<Entity, u32> = HashMap::new();
+    // 已经销毁的激光，每道激光只命中一个敌人
+    let mut spent = HashSet::new();
+
+    for event in collision_events.iter() {
+        let Some((laser, enemy_entity)) = event.between(Layers::PLAYER_PROJECTILE, Layers::ENEMY)
+        else {
+            continue;
+        };
+        if spent.contains(&laser) {
+            continue;
+        }
+        let Ok(zone) = enemy_query.get(enemy_entity) else {
+            continue;
+        };
+        // 击中 Boss 的受击区域时伤害结算到 Boss 本身
+        let (target, multiplier) =
+            zone.map_or((enemy_entity, 1), |zone| (zone.owner, zone.multiplier));
+        let Ok(health) = health_query.get(target) else {
+            continue;
+        };
+        let health = remaining.entry(target).or_insert(health.current);
+        if *health == 0 {
+            continue;
+        }
+        let Ok((damage, mut piercing)) = laser_query.get_mut(laser) else {
+            continue;
+        };
+        // 穿透弹道只命中同一目标一次
+        if let Some(piercing) = &piercing {
+            if piercing.has_hit(target) {
+                continue;
+            }
+        }
+
+        let amount = damage.0 * multiplier;
+        *health = health.saturating_sub(amount);
+        damage_events.send(DamageEvent { target, amount });
+        // 穿透弹道继续飞行，否则销毁激光
+        let pierced = piercing
+            .as_mut()
+            .is_some_and(|piercing| piercing.pierce(target));
+        if !pierced {
+            commands.entity(laser).despawn();
+            spent.insert(laser);
+        }
+    }
+}
+
+/// 伤害结算系统
+///
+/// 扣除生命值，生命值耗尽时销毁实体并发送 `EnemyKilledEvent` 或 `PlayerKilledEvent`，
+/// 否则闪烁提示受击。得分、音效与爆炸动画由各自的系统响应事件处理。
+/// Boss 的伤害由 boss_damage_system 单独结算
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn damage_system(
+    mut commands: Commands,
+    mut damage_events: EventReader<DamageEvent>,
+    mut enemy_killed_events: EventWriter<EnemyKilledEvent>,
+    mut player_killed_events: EventWriter<PlayerKilledEvent>,
+    config: Res<GameConfig>,
+    clock: Res<GameClock>,
+    active_power_ups: Res<ActivePowerUps>,
+    mut game_data: ResMut<GameData>,
+    mut max_enemy: ResMut<MaxEnemy>,
+    mut player_state: ResMut<PlayerState>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut query: Query<
+        (
+            &Transform,
+            &mut Health,
+            &mut Sprite,
+            Option<&mut HitFlash>,
+            Option<&EnemyKind>,
+        ),
+        Without<Boss>,
+    >,
+) {
+    // 本次运行中开始闪烁的实体
+    let mut flashed = HashSet::new();
+    for event in damage_events.iter() {
+        let Ok((tf, mut health, mut sprite, flash, kind)) = query.get_mut(event.target) else {
+            continue;
+        };
+        // 同一步长内已经被摧毁
+        if health.current == 0 {
+            continue;
+        }
+        // 护盾道具生效期间玩家不受伤害
+        if kind.is_none() && active_power_ups.is_active(PowerUpKind::Shield) {
+            continue;
+        }
+
+        if !health.damage(event.amount) {
+            // 受击闪烁，闪烁期间再次受击只重置计时
+            match flash {
+                Some(mut flash) => flash.timer.reset(),
+                // 同一步长内再次受击时 HitFlash 还未插入，精灵已是闪烁颜色，不能当作原来的颜色保存
+                None if !flashed.insert(event.target) => {}
+                None => {
+                    commands
+                        .entity(event.target)
+                        .insert(HitFlash::new(sprite.color));
+                    sprite.color = HIT_FLASH_COLOR;
+                }
+            }
+            continue;
+        }
+
+        match kind {
+            // 敌人被消灭
+            Some(kind) => {
+                // 敌人数量 -1
+                if max_enemy.0 != 0 {
+                    max_enemy.0 -= 1;
+                }
+                enemy_killed_events.send(EnemyKilledEvent {
+                    score: config.enemies.get(*kind).score,
+                    position: tf.translation,
+                });
+            }
+            // 玩家被摧毁
+            None => {
+                // 生命耗尽时游戏结束，否则等待 player_spawn_system 重生
+                if !game_data.lose_life() {
+                    next_state.set(GameState::GameOver);
+                }
+                // 记录被命中的时刻
+                player_state.shot(clock.elapsed_seconds_f64());
+                player_killed_events.send(PlayerKilledEvent {
+                    position: tf.translation,
+                });
+            }
+        }
+
+        commands.entity(event.target).despawn();
+    }
+}
+
+/// 记分系统
+///
+/// 敌人被消灭时加分，得分倍数道具生效期间分数翻倍
+pub fn score_system(
+    mut killed_events: EventReader<EnemyKilledEvent>,
+    config: Res<GameConfig>,
+    active_power_ups: Res<ActivePowerUps>,
+    mut game_data: ResMut<GameData>,
+) {
+    for event in killed_events.iter() {
+        let mut score = event.score;
+        if active_power_ups.is_active(PowerUpKind::ScoreMultiplier) {
+            score *= config.power_ups.score_multiplier;
+        }
+        game_data.add_score(score);
+    }
+}
+
+/// 爆炸音效系统
+pub fn explosion_sound_system(
+    mut enemy_killed_events: EventReader<EnemyKilledEvent>,
+    mut player_killed_events: EventReader<PlayerKilledEvent>,
+    audio_source: Res<GameAudio>,
+    audio: Res<Audio>,
+) {
+    for _ in enemy_killed_events.iter() {
+        audio.play(audio_source.enemy_explosion.clone());
+    }
+    for _ in player_killed_events.iter() {
+        audio.play(audio_source.player_explosion.clone());
+    }
+}
+
+/// 爆炸效果系统
+///
+/// 在敌人与玩家被摧毁的位置产生爆炸动画
+pub fn explosion_effect_system(
+    mut commands: Commands,
+    mut enemy_killed_events: EventReader<EnemyKilledEvent>,
+    mut player_killed_events: EventReader<PlayerKilledEvent>,
+) {
+    let positions = enemy_killed_events
+        .iter()
+        .map(|event| event.position)
+        .chain(player_killed_events.iter().map(|event| event.position));
+    for position in positions {
+        commands.spawn(ExplosionToSpawn(position));
+    }
+}
+
+/// 受击闪烁系统
+pub fn hit_flash_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut HitFlash, &mut Sprite)>,
+) {
+    for (entity, mut flash, mut sprite) in query.iter_mut() {
+        flash.timer.tick(time.delta());
+        if flash.timer.finished() {
+            sprite.color = flash.color;
+            commands.entity(entity).remove::<HitFlash>();
+        }
+    }
+}
+
+/// 是否播放爆炸动画
+fn explosion_criteria(state: Res<State<GameState>>) -> bool {
+    matches!(state.0, GameState::InGame | GameState::GameOver)
+}
+
+/// 爆炸画面生成系统
+pub fn explosion_to_spawn_system(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    query: Query<(Entity, &ExplosionToSpawn)>,
+) {
+    for (explosion_spawn_entity, explosion_to_spawn) in query.iter() {
+        commands
+            .spawn(SpriteSheetBundle {
+                texture_atlas: game_textures.explosion.clone(),
+                transform: Transform {
+                    translation: explosion_to_spawn.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .insert(Explosion)
+            .insert(ExplosionTimer::default());
+
+        commands.entity(explosion_spawn_entity).despawn();
+    }
+}
+
+/// 爆炸动画系统
+pub fn explosion_animation_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    mut query: Query<(Entity, &mut ExplosionTimer, &mut TextureAtlasSprite), With<Explosion>>,
+) {
+    for (entity, mut timer, mut texture_atlas_sprite) in query.iter_mut() {
+        timer.0.tick(time.delta());
+
+        if timer.0.finished() {
+            texture_atlas_sprite.index += 1;
+            if texture_atlas_sprite.index >= config.sprites.explosion.frames {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+/// 分数更新系统
+pub fn score_display_update_system(
+    game_data: Res<GameData>,
+    mut query: Query<&mut Text, With<DisplayScore>>,
+) {
+    for mut text in &mut query {
+        let new_str: String = format!(
+            "SCORE:{} LIVES:{} WAVE:{}",
+            game_data.get_score(),
+            game_data.get_lives(),
+            game_data.get_wave()
+        );
+        text.sections[0].value = new_str;
+    }
+}
+
+/// 护盾条更新系统，玩家不存在时护盾条为空，护盾道具生效期间变为金色
+pub fn shield_bar_update_system(
+    active_power_ups: Res<ActivePowerUps>,
+    player_query: Query<&Health, With<Player>>,
+    mut bar_query: Query<&mut Sprite, With<ShieldBar>>,
+) {
+    let ratio = player_query
+        .get_single()
+        .map_or(0., |health| health.current as f32 / health.max as f32);
+    let color = if active_power_ups.is_active(PowerUpKind::Shield) {
+        Color::GOLD
+    } else {
+        Color::CYAN
+    };
+    for mut sprite in &mut bar_query {
+        sprite.color = color;
+        sprite.custom_size = Some(Vec2::new(SHIELD_BAR_SIZE.0 * ratio, SHIELD_BAR_SIZE.1));
+    }
+}
@@ -1,13 +1,18 @@
-use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy::{prelude::*, time::common_conditions::on_fixed_timer};
 use std::time::Duration;
 
 use crate::{
-    components::{FromPlayer, Laser, Movable, Player, SpriteSize, Velocity},
+    action::{Action, ActionState},
+    collision::{shape::ColliderShapes, Collider},
+    components::{FireControl, FromPlayer, Health, Movable, Player, SpriteSize, Velocity},
+    config::GameConfig,
+    powerup::{ActivePowerUps, PowerUpKind},
     resource::GameAudio,
-    resource::PlayerState,
-    resource::WinSize,
+    resource::Playfield,
+    resource::{GameClock, PlayerInput, PlayerState},
     resource::{GameState, GameTextures},
-    BASE_SPEED, PLAYER_LASER_SIZE, PLAYER_RESPAWN_DELAY, PLAYER_SIZE, SPRITE_SCALE, TIME_STEP,
+    weapon::{effective_weapon, Weapon, WeaponKind},
+    GameSet,
 };
 
 pub struct PlayerPlugin;
@@ -18,20 +23,26 @@ impl Plugin for PlayerPlugin {
         // StartupSet::PostStartup 在 StartupSet::Startup 后运行一次
         // add_startup_system(player_spawn_system.in_base_set(StartupSet::PostStartup))
         // add_system 每帧都运行 , 可以在函数后通过 run_if 传入 bool 类型的条件进行限制
+        // 输入每帧读取，生成、攻击、移动在固定步长中运行
         app.insert_resource(PlayerState::default())
+            .init_resource::<PlayerInput>()
             .add_system(
                 player_spawn_system
-                    .run_if(on_timer(Duration::from_secs_f32(0.5)))
-                    .in_set(OnUpdate(GameState::InGame)),
+                    .run_if(on_fixed_timer(Duration::from_secs_f32(0.5)))
+                    .in_set(GameSet::Spawn)
+                    .in_schedule(CoreSchedule::FixedUpdate),
             )
-            .add_systems(
-                (
-                    player_keyboard_event_system,
-                    player_movable_system,
-                    player_fire_system,
-                )
-                    .in_set(OnUpdate(GameState::InGame)),
-            );
+            .add_system(
+                player_fire_system
+                    .in_set(GameSet::Fire)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                player_movable_system
+                    .in_set(GameSet::Movement)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(player_action_system.in_set(OnUpdate(GameState::InGame)));
     }
 }
 
@@ -39,27 +50,28 @@ impl Plugin for PlayerPlugin {
 fn player_spawn_system(
     mut commands: Commands,
     mut player_state: ResMut<PlayerState>,
-    time: Res<Time>,
+    clock: Res<GameClock>,
+    config: Res<GameConfig>,
     game_textures: Res<GameTextures>,
-    win_size: Res<WinSize>,
+    collider_shapes: Res<ColliderShapes>,
+    playfield: Res<Playfield>,
 ) {
-    let now = time.elapsed_seconds_f64();
+    let now = clock.elapsed_seconds_f64();
     let last_shot = player_state.last_shot;
-    if !player_state.on && (player_state.last_shot == -1. || now - PLAYER_RESPAWN_DELAY > last_shot)
+    if !player_state.on
+        && (player_state.last_shot == -1. || now - config.player_respawn_delay > last_shot)
     {
-        let bottom = -win_size.h / 2.;
+        let bottom = -playfield.h / 2.;
+        let scale = config.sprite_scale;
+        let size = config.sprites.player.size;
 
         // 创建组件实体，并返回对应的 EntityCommand
         commands
             .spawn(SpriteBundle {
                 texture: game_textures.player.clone(),
                 transform: Transform {
-                    translation: Vec3::new(
-                        0.,
-                        bottom + PLAYER_SIZE.1 / 2. * SPRITE_SCALE + 5.0,
-                        10.,
-                    ),
-                    scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.0),
+                    translation: Vec3::new(0., bottom + size.1 / 2. * scale + 5.0, 10.),
+                    scale: Vec3::new(scale, scale, 1.0),
                     ..default()
                 },
                 ..SpriteBundle::default()
@@ -68,7 +80,13 @@ fn player_spawn_system(
             .insert(Movable {
                 auto_despawn: false,
             })
-            .insert(SpriteSize::from(PLAYER_SIZE))
+            .insert(SpriteSize::from(size))
+            .insert(Collider::PLAYER)
+            .insert(collider_shapes.get(&config.sprites.player))
+            .insert(Health::new(config.player_hit_points))
+            .insert(config.weapons.get(WeaponKind::Twin).clone())
+            .insert(WeaponKind::Twin)
+            .insert(FireControl::default())
             .insert(Player);
 
         player_state.spawned();
@@ -76,75 +94,112 @@ fn player_spawn_system(
 }
 
 /// 玩家攻击系统
+///
+/// 按住攻击键时在武器冷却结束后自动发射，连射道具生效时冷却缩短，散射道具生效时使用散射武器。
+/// 启用蓄力射击时按住攻击键蓄力，松开时按蓄力时间发射蓄力弹道或普通弹道
+#[allow(clippy::too_many_arguments)]
 fn player_fire_system(
     mut commands: Commands,
     audio_source: Res<GameAudio>,
     audio: Res<Audio>,
-    kb: Res<Input<KeyCode>>,
+    fixed_time: Res<FixedTime>,
+    mut player_input: ResMut<PlayerInput>,
+    active_power_ups: Res<ActivePowerUps>,
+    config: Res<GameConfig>,
     game_textures: Res<GameTextures>,
-    query: Query<&Transform, With<Player>>,
+    mut query: Query<(&Transform, &Weapon, &WeaponKind, &mut FireControl), With<Player>>,
 ) {
-    if let Ok(player_tf) = query.get_single() {
-        // just_released 松开按键
-        if kb.just_released(KeyCode::Space) {
-            audio.play(audio_source.player_laser.clone());
-            let (x, y) = (player_tf.translation.x, player_tf.translation.y);
-
-            let x_offset = PLAYER_SIZE.0 / 2. * SPRITE_SCALE - 5.;
-
-            // 激光生成闭包 因为这里使用了 commands 生成新的包 所以这里的闭包需要定义为 mut 类型
-            let mut spawn_laser = |x_offset: f32| {
-                commands
-                    .spawn(SpriteBundle {
-                        texture: game_textures.player_laser.clone(),
-                        transform: Transform {
-                            translation: Vec3::new(x + x_offset, y + 15., 1.),
-                            scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 0.),
-                            ..Default::default()
-                        },
-                        ..Default::default()
-                    })
-                    .insert(Laser)
-                    .insert(FromPlayer)
-                    .insert(SpriteSize::from(PLAYER_LASER_SIZE))
-                    .insert(Movable { auto_despawn: true })
-                    .insert(Velocity::new(0., 1.));
-            };
-            spawn_laser(x_offset);
-            spawn_laser(-x_offset);
+    // 消费本帧的攻击输入
+    let pressed = std::mem::take(&mut player_input.fire);
+    let released = std::mem::take(&mut player_input.fire_released);
+    let held = player_input.fire_held;
+    let Ok((player_tf, weapon, &kind, mut fire_control)) = query.get_single_mut() else {
+        return;
+    };
+
+    let delta = fixed_time.period;
+    fire_control.cooldown.tick(delta);
+    let (weapon, kind) = effective_weapon(&config, &active_power_ups, weapon, kind);
+
+    let charge_shot = &config.charge_shot;
+    let weapon = if charge_shot.enabled {
+        if pressed || held {
+            fire_control.charge += delta.as_secs_f32();
+        }
+        if !released {
+            return;
+        }
+        let charge = std::mem::take(&mut fire_control.charge);
+        match charge_shot.level(charge) {
+            // 蓄力弹道不受冷却限制
+            Some(level) => weapon.charged(charge_shot, level),
+            None if fire_control.ready() => weapon.clone(),
+            None => return,
         }
+    } else {
+        if !(pressed || held) || !fire_control.ready() {
+            return;
+        }
+        weapon.clone()
+    };
+
+    let mut cooldown = weapon.cooldown;
+    if active_power_ups.is_active(PowerUpKind::RapidFire) {
+        cooldown = cooldown.min(config.power_ups.rapid_fire_interval as f32);
     }
+    fire_control.start_cooldown(cooldown);
+
+    audio.play(audio_source.player_laser.clone());
+    let origin = player_tf.translation.truncate().extend(1.) + Vec3::new(0., 15., 0.);
+    weapon.fire(
+        &mut commands,
+        game_textures.weapon(kind),
+        origin,
+        0.,
+        config.sprite_scale,
+        FromPlayer,
+    );
 }
 
-/// 键盘事件系统
-fn player_keyboard_event_system(
-    kb: Res<Input<KeyCode>>,
-    mut next_state: ResMut<NextState<GameState>>,
-    mut query: Query<&mut Velocity, With<Player>>,
+/// 玩家输入系统
+fn player_action_system(
+    actions: Res<ActionState>,
+    config: Res<GameConfig>,
+    mut player_input: ResMut<PlayerInput>,
+    mut query: Query<(&mut Velocity, &mut Weapon, &mut WeaponKind), With<Player>>,
 ) {
-    if let Ok(mut velocity) = query.get_single_mut() {
-        // pressed 按下按键
-        if kb.pressed(KeyCode::Left) {
-            velocity.x = -1.
-        } else if kb.pressed(KeyCode::Right) {
-            velocity.x = 1.
-        } else if kb.just_pressed(KeyCode::P) {
-            next_state.set(GameState::Paused);
-        } else {
-            velocity.x = 0.
+    if let Ok((mut velocity, mut weapon, mut weapon_kind)) = query.get_single_mut() {
+        // 切换武器
+        if actions.just_pressed(Action::SwitchWeapon) {
+            *weapon_kind = weapon_kind.next();
+            *weapon = config.weapons.get(*weapon_kind).clone();
         }
+
+        // 按下、松开攻击键时写入输入，等待固定步长中的攻击系统处理
+        if actions.just_pressed(Action::Fire) {
+            player_input.fire = true;
+        }
+        if actions.just_released(Action::Fire) {
+            player_input.fire_released = true;
+        }
+        player_input.fire_held = actions.pressed(Action::Fire);
+
+        // 方向键为全速，摇杆按推动幅度决定速度
+        velocity.x = actions.axis();
     };
 }
 
 /// 玩家移动系统
-fn player_movable_system(
-    win_size: Res<WinSize>,
+pub(crate) fn player_movable_system(
+    fixed_time: Res<FixedTime>,
+    config: Res<GameConfig>,
+    playfield: Res<Playfield>,
     mut query: Query<(&Velocity, &mut Transform), With<Player>>,
 ) {
-    let max_w = win_size.w / 2.;
+    let max_w = playfield.w / 2.;
 
     for (velocity, mut transform) in query.iter_mut() {
-        let distance = velocity.x * BASE_SPEED * TIME_STEP;
+        let distance = velocity.x * config.base_speed * fixed_time.period.as_secs_f32();
         let new_x = transform.translation.x + distance;
         if -max_w <= new_x && new_x <= max_w {
             // 移动位置
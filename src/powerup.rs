@@ -0,0 +1,268 @@
+use bevy::{prelude::*, text::Text2dBounds, utils::HashMap};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collision::{Collider, Layers},
+    components::{HudAnchor, Movable, PowerUpText, SpriteSize, Velocity},
+    config::GameConfig,
+    events::{CollisionEvent, EnemyKilledEvent},
+    resource::{GameData, GameRng, GameState, GameTextures},
+    GameSet,
+};
+
+/// 道具类型组件
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PowerUpKind {
+    /// 散射：期间换用散射武器
+    SpreadShot,
+    /// 连射：缩短武器冷却时间
+    RapidFire,
+    /// 护盾：期间不受伤害
+    Shield,
+    /// 额外生命
+    ExtraLife,
+    /// 得分倍数
+    ScoreMultiplier,
+}
+
+impl PowerUpKind {
+    /// 全部道具类型，也是 HUD 中的展示顺序
+    pub const ALL: [PowerUpKind; 5] = [
+        PowerUpKind::SpreadShot,
+        PowerUpKind::RapidFire,
+        PowerUpKind::Shield,
+        PowerUpKind::ExtraLife,
+        PowerUpKind::ScoreMultiplier,
+    ];
+
+    /// 道具图标颜色
+    pub fn color(&self) -> Color {
+        match self {
+            PowerUpKind::SpreadShot => Color::ORANGE,
+            PowerUpKind::RapidFire => Color::YELLOW,
+            PowerUpKind::Shield => Color::CYAN,
+            PowerUpKind::ExtraLife => Color::GREEN,
+            PowerUpKind::ScoreMultiplier => Color::VIOLET,
+        }
+    }
+
+    /// 道具图标上的字母
+    pub fn letter(&self) -> &'static str {
+        match self {
+            PowerUpKind::SpreadShot => "S",
+            PowerUpKind::RapidFire => "R",
+            PowerUpKind::Shield => "D",
+            PowerUpKind::ExtraLife => "L",
+            PowerUpKind::ScoreMultiplier => "X",
+        }
+    }
+
+    /// HUD 中展示的名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            PowerUpKind::SpreadShot => "SPREAD",
+            PowerUpKind::RapidFire => "RAPID",
+            PowerUpKind::Shield => "SHIELD",
+            PowerUpKind::ExtraLife => "LIFE",
+            PowerUpKind::ScoreMultiplier => "SCORE",
+        }
+    }
+
+    /// 是否为限时效果，额外生命拾取后立即生效
+    pub fn is_timed(&self) -> bool {
+        !matches!(self, PowerUpKind::ExtraLife)
+    }
+}
+
+/// 生效中的限时道具
+#[derive(Resource, Default)]
+pub struct ActivePowerUps {
+    timers: HashMap<PowerUpKind, Timer>,
+}
+
+impl ActivePowerUps {
+    /// 激活道具，已生效的道具重新计时
+    pub fn activate(&mut self, kind: PowerUpKind, seconds: f32) {
+        self.timers
+            .insert(kind, Timer::from_seconds(seconds, TimerMode::Once));
+    }
+
+    /// 道具是否生效中
+    pub fn is_active(&self, kind: PowerUpKind) -> bool {
+        self.timers.contains_key(&kind)
+    }
+
+    /// 道具剩余时间 (秒)
+    pub fn remaining_secs(&self, kind: PowerUpKind) -> Option<f32> {
+        self.timers.get(&kind).map(|timer| timer.remaining_secs())
+    }
+
+    /// 推进计时，移除到期的道具
+    pub fn tick(&mut self, delta: std::time::Duration) {
+        self.timers.retain(|_, timer| !timer.tick(delta).finished());
+    }
+
+    /// 清除全部道具
+    pub fn clear(&mut self) {
+        self.timers.clear();
+    }
+}
+
+pub struct PowerUpPlugin;
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        // 掉落、拾取在碰撞判定之后运行，计时在固定步长中推进
+        app.init_resource::<ActivePowerUps>()
+            .add_system(power_up_reset_system.in_schedule(OnExit(GameState::Welcome)))
+            .add_system(
+                power_up_timer_system
+                    .in_set(GameSet::Spawn)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_systems(
+                (power_up_drop_system, power_up_pick_up_system)
+                    .chain()
+                    .after(crate::damage_system)
+                    // 本步长的得分使用拾取前的倍数
+                    .after(crate::score_system)
+                    .in_set(GameSet::Collision)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_startup_system(power_up_text_setup_system.in_base_set(StartupSet::PostStartup))
+            .add_system(power_up_text_system);
+    }
+}
+
+/// 开始新的一局时清除道具效果
+fn power_up_reset_system(mut active: ResMut<ActivePowerUps>) {
+    active.clear();
+}
+
+/// 道具计时系统
+fn power_up_timer_system(fixed_time: Res<FixedTime>, mut active: ResMut<ActivePowerUps>) {
+    active.tick(fixed_time.period);
+}
+
+/// 道具掉落系统，敌人被消灭时按掉落表随机生成道具
+fn power_up_drop_system(
+    mut commands: Commands,
+    mut killed_events: EventReader<EnemyKilledEvent>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    game_textures: Res<GameTextures>,
+) {
+    let power_ups = &config.power_ups;
+    for event in killed_events.iter() {
+        if !rng.gen_bool(power_ups.drop_chance) {
+            continue;
+        }
+        let Some(kind) = rng.pick_weighted(&power_ups.drop_table) else {
+            continue;
+        };
+
+        let size = Vec2::from(power_ups.size);
+        commands
+            .spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: kind.color(),
+                    custom_size: Some(size),
+                    ..Default::default()
+                },
+                transform: Transform::from_xyz(event.position.x, event.position.y, 5.),
+                ..Default::default()
+            })
+            .insert(kind)
+            .insert(SpriteSize(size))
+            .insert(Collider::PICKUP)
+            .insert(Movable { auto_despawn: true })
+            .insert(Velocity::new(0., -power_ups.fall_speed))
+            .with_children(|parent| {
+                parent.spawn(Text2dBundle {
+                    text: Text::from_section(
+                        kind.letter(),
+                        TextStyle {
+                            font: game_textures.font.clone(),
+                            font_size: size.y * 0.8,
+                            color: Color::BLACK,
+                        },
+                    )
+                    .with_alignment(TextAlignment::Center),
+                    text_2d_bounds: Text2dBounds { size },
+                    transform: Transform::from_xyz(0., 0., 1.),
+                    ..Default::default()
+                });
+            });
+    }
+}
+
+/// 道具拾取系统，处理玩家与道具的碰撞事件
+fn power_up_pick_up_system(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    config: Res<GameConfig>,
+    mut active: ResMut<ActivePowerUps>,
+    mut game_data: ResMut<GameData>,
+    power_up_query: Query<&PowerUpKind>,
+) {
+    let pick_ups = collision_events
+        .iter()
+        .filter_map(|event| event.between(Layers::PLAYER, Layers::PICKUP));
+    for (_, entity) in pick_ups {
+        let Ok(&kind) = power_up_query.get(entity) else {
+            continue;
+        };
+
+        if kind.is_timed() {
+            active.activate(kind, config.power_ups.duration);
+        } else {
+            game_data.add_life();
+        }
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// 生成道具状态控件
+fn power_up_text_setup_system(mut commands: Commands, game_textures: Res<GameTextures>) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: game_textures.font.clone(),
+                    font_size: 18.,
+                    color: Color::ANTIQUE_WHITE,
+                },
+            )
+            .with_alignment(TextAlignment::Left),
+            transform: Transform::from_xyz(0., 0., 11.),
+            text_anchor: bevy::sprite::Anchor::BottomLeft,
+            ..Default::default()
+        },
+        HudAnchor::new(Vec2::new(-1., -1.), Vec2::new(10., 20.)),
+        PowerUpText,
+    ));
+}
+
+/// 道具状态更新系统，展示生效中的道具及剩余时间
+fn power_up_text_system(
+    active: Res<ActivePowerUps>,
+    mut query: Query<&mut Text, With<PowerUpText>>,
+) {
+    if !active.is_changed() {
+        return;
+    }
+
+    let lines: Vec<String> = PowerUpKind::ALL
+        .into_iter()
+        .filter_map(|kind| {
+            active
+                .remaining_secs(kind)
+                .map(|secs| format!("{} {:.1}", kind.label(), secs))
+        })
+        .collect();
+    for mut text in &mut query {
+        text.sections[0].value = lines.join("\n");
+    }
+}
@@ -0,0 +1,361 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use bevy::{
+    app::AppExit,
+    prelude::*,
+    time::{TimeSystem, TimeUpdateStrategy},
+};
+
+use crate::{
+    action::{ActionState, ActionSystem},
+    config::GameConfig,
+    resource::GameRng,
+};
+
+/// 录像文件标识
+const REPLAY_MAGIC: &[u8; 4] = b"INVR";
+/// 录像文件版本
+const REPLAY_VERSION: u8 = 3;
+
+/// 单帧录像数据
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ReplayFrame {
+    /// 帧间隔
+    pub delta: Duration,
+    /// 动作状态
+    pub actions: ActionState,
+}
+
+impl ReplayFrame {
+    /// 每帧占用的字节数：帧间隔 (纳秒) u64 + 三个动作掩码 u16 + 横向移动量 f32
+    const SIZE: usize = 18;
+
+    fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        // u64 纳秒足够记录任意长的卡顿 (约 584 年)，回放时不会少运行固定步长
+        let nanos = u64::try_from(self.delta.as_nanos()).unwrap_or(u64::MAX);
+        writer.write_all(&nanos.to_le_bytes())?;
+        writer.write_all(&self.actions.pressed.to_le_bytes())?;
+        writer.write_all(&self.actions.just_pressed.to_le_bytes())?;
+        writer.write_all(&self.actions.just_released.to_le_bytes())?;
+        writer.write_all(&self.actions.axis.to_le_bytes())
+    }
+
+    fn read(bytes: &[u8; Self::SIZE]) -> Self {
+        let u16_at = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]);
+        let mut nanos = [0; 8];
+        nanos.copy_from_slice(&bytes[..8]);
+        Self {
+            delta: Duration::from_nanos(u64::from_le_bytes(nanos)),
+            actions: ActionState {
+                pressed: u16_at(8),
+                just_pressed: u16_at(10),
+                just_released: u16_at(12),
+                axis: f32::from_le_bytes([bytes[14], bytes[15], bytes[16], bytes[17]]),
+            },
+        }
+    }
+}
+
+/// 录像
+///
+/// 文件格式 (小端)：`INVR` + 版本 u8 + 种子 u64 + 配置长度 u32 + RON 格式的配置，之后每帧 18 字节。
+/// 配置包含游戏模式与固定步长，回放时不读取磁盘上的配置文件与命令行中的模式
+#[derive(Clone, Debug, Default)]
+pub struct Replay {
+    /// 随机数种子
+    pub seed: u64,
+    /// 录制时生效的游戏配置
+    pub config: GameConfig,
+    /// 帧数据
+    pub frames: Vec<ReplayFrame>,
+}
+
+impl Replay {
+    /// 从文件读取录像
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        let mut version = [0; 1];
+        reader.read_exact(&mut version)?;
+        if &magic != REPLAY_MAGIC || version[0] != REPLAY_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not an invaders replay file or unsupported version",
+            ));
+        }
+
+        let mut seed = [0; 8];
+        reader.read_exact(&mut seed)?;
+        let mut config_len = [0; 4];
+        reader.read_exact(&mut config_len)?;
+        let mut config = vec![0; u32::from_le_bytes(config_len) as usize];
+        reader.read_exact(&mut config)?;
+        let config = std::str::from_utf8(&config)
+            .map_err(|err| err.to_string())
+            .and_then(|config| ron::from_str::<GameConfig>(config).map_err(|err| err.to_string()))
+            .and_then(|config| {
+                config
+                    .validate()
+                    .map(|_| config)
+                    .map_err(|err| err.to_string())
+            })
+            .map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid config in replay: {err}"),
+                )
+            })?;
+
+        let mut frames = Vec::new();
+        let mut bytes = [0; ReplayFrame::SIZE];
+        loop {
+            match reader.read_exact(&mut bytes) {
+                Ok(()) => frames.push(ReplayFrame::read(&bytes)),
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(Self {
+            seed: u64::from_le_bytes(seed),
+            config,
+            frames,
+        })
+    }
+
+    fn write_header(writer: &mut impl Write, seed: u64, config: &GameConfig) -> io::Result<()> {
+        let config = ron::to_string(config)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        let config_len = u32::try_from(config.len())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+
+        writer.write_all(REPLAY_MAGIC)?;
+        writer.write_all(&[REPLAY_VERSION])?;
+        writer.write_all(&seed.to_le_bytes())?;
+        writer.write_all(&config_len.to_le_bytes())?;
+        writer.write_all(config.as_bytes())
+    }
+}
+
+/// 录像系统集，在 `ActionSystem` 之后录制或写入动作状态
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ReplaySystem;
+
+/// 录像插件
+///
+/// 回放时需要使用录像中的种子与配置创建 `InvadersPlugin`
+pub enum ReplayPlugin {
+    /// 录制到指定文件
+    Record(PathBuf),
+    /// 回放录像，替代键盘、手柄输入与帧间隔
+    Playback(Box<Replay>),
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        match self {
+            ReplayPlugin::Record(path) => {
+                app.insert_resource(ReplayRecordPath(path.clone()))
+                    .add_startup_system(replay_record_setup_system)
+                    .add_system(
+                        replay_record_system
+                            .in_set(ReplaySystem)
+                            .after(ActionSystem)
+                            .in_base_set(CoreSet::PreUpdate),
+                    )
+                    .add_system(replay_flush_system.in_base_set(CoreSet::Last));
+            }
+            ReplayPlugin::Playback(replay) => {
+                app.insert_resource(ReplayPlayback {
+                    frames: replay.frames.clone(),
+                    cursor: 0,
+                })
+                .add_system(
+                    replay_time_system
+                        .before(TimeSystem)
+                        .in_base_set(CoreSet::First),
+                )
+                .add_system(
+                    replay_input_system
+                        .in_set(ReplaySystem)
+                        .after(ActionSystem)
+                        .in_base_set(CoreSet::PreUpdate),
+                );
+            }
+        }
+    }
+}
+
+/// 录像文件路径
+#[derive(Resource)]
+struct ReplayRecordPath(PathBuf);
+
+/// 录像写入器
+#[derive(Resource)]
+struct ReplayRecorder {
+    writer: BufWriter<File>,
+}
+
+/// 回放进度
+#[derive(Resource)]
+struct ReplayPlayback {
+    frames: Vec<ReplayFrame>,
+    /// 当前帧下标
+    cursor: usize,
+}
+
+impl ReplayPlayback {
+    fn current(&self) -> Option<&ReplayFrame> {
+        self.cursor.checked_sub(1).and_then(|i| self.frames.get(i))
+    }
+}
+
+/// 创建录像文件并写入种子与配置
+fn replay_record_setup_system(
+    mut commands: Commands,
+    path: Res<ReplayRecordPath>,
+    rng: Res<GameRng>,
+    config: Res<GameConfig>,
+) {
+    let result = File::create(&path.0).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        Replay::write_header(&mut writer, rng.seed(), &config)?;
+        Ok(writer)
+    });
+
+    match result {
+        Ok(writer) => {
+            info!("recording replay to {}", path.0.display());
+            commands.insert_resource(ReplayRecorder { writer });
+        }
+        Err(err) => error!("failed to create replay {}: {err}", path.0.display()),
+    }
+}
+
+/// 每帧记录帧间隔与动作状态
+fn replay_record_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    actions: Res<ActionState>,
+    recorder: Option<ResMut<ReplayRecorder>>,
+) {
+    let Some(mut recorder) = recorder else {
+        return;
+    };
+
+    let frame = ReplayFrame {
+        delta: time.delta(),
+        actions: *actions,
+    };
+    if let Err(err) = frame.write(&mut recorder.writer) {
+        error!("failed to write replay, recording stopped: {err}");
+        commands.remove_resource::<ReplayRecorder>();
+    }
+}
+
+/// 退出时写入缓冲区中剩余的录像数据
+fn replay_flush_system(
+    mut exit_events: EventReader<AppExit>,
+    recorder: Option<ResMut<ReplayRecorder>>,
+) {
+    if exit_events.iter().next().is_none() {
+        return;
+    }
+    if let Some(mut recorder) = recorder {
+        if let Err(err) = recorder.writer.flush() {
+            error!("failed to flush replay: {err}");
+        }
+    }
+}
+
+/// 回放时使用录像中的帧间隔更新 `Time`，录像结束后恢复实时
+fn replay_time_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut update_strategy: ResMut<TimeUpdateStrategy>,
+    mut playback: ResMut<ReplayPlayback>,
+) {
+    let Some(frame) = playback.frames.get(playback.cursor).copied() else {
+        info!("replay finished, returning to live input");
+        *update_strategy = TimeUpdateStrategy::Automatic;
+        commands.remove_resource::<ReplayPlayback>();
+        return;
+    };
+
+    let last_update = time.last_update().unwrap_or_else(|| time.startup());
+    *update_strategy = TimeUpdateStrategy::ManualInstant(last_update + frame.delta);
+    playback.cursor += 1;
+}
+
+/// 回放时使用录像中的动作状态替代键盘与手柄输入
+fn replay_input_system(playback: Option<Res<ReplayPlayback>>, mut actions: ResMut<ActionState>) {
+    if let Some(frame) = playback.as_ref().and_then(|playback| playback.current()) {
+        *actions = frame.actions;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::Action;
+
+    #[test]
+    fn frame_round_trip() {
+        let mut actions = ActionState::default();
+        actions.set(Action::Fire, true, true, false);
+        actions.set(Action::Left, false, false, true);
+        actions.set(Action::Confirm, true, false, false);
+        actions.set_axis(-0.375);
+
+        // 超过 u32 纳秒的卡顿也要完整保留
+        for delta in [
+            Duration::ZERO,
+            Duration::from_nanos(16_666_667),
+            Duration::from_secs(10),
+            Duration::from_secs(3600),
+        ] {
+            let frame = ReplayFrame { delta, actions };
+            let mut bytes = Vec::new();
+            frame.write(&mut bytes).unwrap();
+            assert_eq!(bytes.len(), ReplayFrame::SIZE);
+
+            let bytes: [u8; ReplayFrame::SIZE] = bytes.try_into().unwrap();
+            assert_eq!(ReplayFrame::read(&bytes), frame);
+        }
+    }
+
+    #[test]
+    fn header_round_trip() {
+        let config = GameConfig {
+            mode: crate::config::GameMode::Grid,
+            time_step: 1. / 144.,
+            ..Default::default()
+        };
+        let frame = ReplayFrame {
+            delta: Duration::from_millis(7),
+            actions: ActionState::default(),
+        };
+
+        let path = std::env::temp_dir().join(format!(
+            "invaders-header-round-trip-{}.invr",
+            std::process::id()
+        ));
+        let mut writer = BufWriter::new(File::create(&path).unwrap());
+        Replay::write_header(&mut writer, 42, &config).unwrap();
+        frame.write(&mut writer).unwrap();
+        drop(writer);
+
+        let replay = Replay::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(replay.seed, 42);
+        assert_eq!(replay.config, config);
+        assert_eq!(replay.frames, vec![frame]);
+    }
+}
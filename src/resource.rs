@@ -2,31 +2,162 @@ use bevy::{
     prelude::{AudioSource, Handle, Image, Resource, States},
     sprite::TextureAtlas,
     text::Font,
+    utils::HashMap,
 };
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+
+use crate::{constants::PLAYER_LIVES, enemy::kind::EnemyKind, weapon::WeaponKind};
+use std::time::Duration;
 
 /// 游戏窗口大小资源
+///
+/// 随窗口缩放事件更新，只用于镜头与 HUD 定位，游戏逻辑使用固定的 `Playfield`
 #[derive(Resource)]
 pub struct WinSize {
     pub w: f32,
     pub h: f32,
 }
 
-/// 游戏图像资源
+/// 游戏区域大小资源
+///
+/// 固定的逻辑大小，与窗口大小无关，镜头缩放使整个游戏区域始终可见，多出的部分用黑边遮挡
+#[derive(Resource, Clone, Copy)]
+pub struct Playfield {
+    pub w: f32,
+    pub h: f32,
+}
+
+impl Playfield {
+    /// 窗口中可见区域的大小 (逻辑坐标)，窗口比例与游戏区域不同时可见区域更宽或更高
+    pub fn visible_size(&self, win_size: &WinSize) -> (f32, f32) {
+        if win_size.w <= 0. || win_size.h <= 0. {
+            return (self.w, self.h);
+        }
+        let scale = (self.w / win_size.w).max(self.h / win_size.h);
+        (win_size.w * scale, win_size.h * scale)
+    }
+}
+
+/// 游戏时钟
+///
+/// 只在固定步长调度中推进，用于替代与帧率相关的 `Time`
+#[derive(Resource, Default)]
+pub struct GameClock {
+    elapsed: Duration,
+}
+
+impl GameClock {
+    /// 推进一个步长
+    pub fn tick(&mut self, delta: Duration) {
+        self.elapsed += delta;
+    }
+
+    /// 获取游戏开始后经过的时间 (秒)
+    pub fn elapsed_seconds_f64(&self) -> f64 {
+        self.elapsed.as_secs_f64()
+    }
+}
+
+/// 游戏随机数资源
+///
+/// 所有随机决策 (阵型、敌人攻击) 都从这里取值，相同的种子与输入会得到相同的游戏过程
 #[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// 获取种子
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// 按权重随机选择，权重全部为 0 时返回 `None`
+    pub fn pick_weighted<T: Copy>(&mut self, weights: &[(T, u32)]) -> Option<T> {
+        let total: u32 = weights.iter().map(|(_, weight)| weight).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = self.gen_range(0..total);
+        for &(value, weight) in weights {
+            if roll < weight {
+                return Some(value);
+            }
+            roll -= weight;
+        }
+        None
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+/// 游戏图像资源
+#[derive(Resource, Default)]
 pub struct GameTextures {
     pub background: Handle<Image>,
     pub player: Handle<Image>,
-    pub player_laser: Handle<Image>,
-    pub enemy: Handle<Image>,
-    pub enemy_laser: Handle<Image>,
+    pub enemies: HashMap<EnemyKind, Handle<Image>>,
+    pub weapons: HashMap<WeaponKind, Handle<Image>>,
+    pub boss: Handle<Image>,
     pub explosion: Handle<TextureAtlas>,
     pub font: Handle<Font>,
 }
 
+impl GameTextures {
+    /// 获取敌人图片，未加载时 (无窗口模式) 返回空句柄
+    pub fn enemy(&self, kind: EnemyKind) -> Handle<Image> {
+        self.enemies.get(&kind).cloned().unwrap_or_default()
+    }
+
+    /// 获取武器弹道图片，未加载时 (无窗口模式) 返回空句柄
+    pub fn weapon(&self, kind: WeaponKind) -> Handle<Image> {
+        self.weapons.get(&kind).cloned().unwrap_or_default()
+    }
+}
+
 /// 敌人最大数量
 #[derive(Resource)]
 pub struct MaxEnemy(pub u32);
 
+/// 玩家输入
+///
+/// 每帧读取键盘后写入，由固定步长中的系统消费，
+/// 避免按键在一帧内的多个步长中被重复处理，或在没有步长的帧中被遗漏
+#[derive(Resource, Default)]
+pub struct PlayerInput {
+    /// 攻击键已按下，等待发射激光
+    pub fire: bool,
+    /// 攻击键是否按住
+    pub fire_held: bool,
+    /// 攻击键已松开，等待发射蓄力弹道
+    pub fire_released: bool,
+}
+
 /// 玩家状态
 #[derive(Resource)]
 pub struct PlayerState {
@@ -56,7 +187,8 @@ impl PlayerState {
     }
 }
 
-#[derive(Resource)]
+/// 游戏声音资源
+#[derive(Resource, Default)]
 pub struct GameAudio {
     pub enemy_explosion: Handle<AudioSource>,
     pub player_explosion: Handle<AudioSource>,
@@ -73,17 +205,38 @@ pub enum GameState {
     InGame,
     /// 暂停
     Paused,
+    /// 游戏结束
+    GameOver,
+    /// 输入排行榜名字
+    NameEntry,
 }
 
 /// 游戏数据
-#[derive(Resource)]
+#[derive(Resource, Clone, Debug, PartialEq)]
 pub struct GameData {
     score: u32,
+    /// 剩余生命数
+    lives: u32,
+    /// 每局初始生命数
+    max_lives: u32,
+    /// 当前波次
+    wave: u32,
+}
+
+impl Default for GameData {
+    fn default() -> Self {
+        Self::new(PLAYER_LIVES)
+    }
 }
 
 impl GameData {
-    pub fn new() -> Self {
-        Self { score: 0 }
+    pub fn new(lives: u32) -> Self {
+        Self {
+            score: 0,
+            lives,
+            max_lives: lives,
+            wave: 1,
+        }
     }
 
     /// 获取当前得分
@@ -92,12 +245,50 @@ impl GameData {
     }
 
     /// 增加得分
-    pub fn add_score(&mut self) {
-        self.score += 1;
+    pub fn add_score(&mut self, points: u32) {
+        self.score += points;
     }
 
     /// 增加得分
     pub fn reset_score(&mut self) {
         self.score = 0;
     }
+
+    /// 获取剩余生命数
+    pub fn get_lives(&self) -> u32 {
+        self.lives
+    }
+
+    /// 获得一条生命
+    pub fn add_life(&mut self) {
+        self.lives += 1;
+    }
+
+    /// 失去全部生命
+    pub fn lose_all_lives(&mut self) {
+        self.lives = 0;
+    }
+
+    /// 失去一条生命，返回是否还有剩余生命
+    pub fn lose_life(&mut self) -> bool {
+        self.lives = self.lives.saturating_sub(1);
+        self.lives > 0
+    }
+
+    /// 获取当前波次
+    pub fn get_wave(&self) -> u32 {
+        self.wave
+    }
+
+    /// 进入下一波
+    pub fn next_wave(&mut self) {
+        self.wave += 1;
+    }
+
+    /// 重置得分、生命与波次，开始新的一局
+    pub fn reset(&mut self) {
+        self.reset_score();
+        self.lives = self.max_lives;
+        self.wave = 1;
+    }
 }
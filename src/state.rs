@@ -1,16 +1,25 @@
 use bevy::{
+    log::error,
     prelude::{
-        Color, Commands, Entity, Input, IntoSystemAppConfig, IntoSystemConfig, IntoSystemConfigs,
-        KeyCode, NextState, OnEnter, OnExit, OnUpdate, Plugin, Query, Res, ResMut, Transform, Vec3,
-        With,
+        Color, Commands, DespawnRecursiveExt, DetectChanges, Entity, IntoSystemAppConfig,
+        IntoSystemConfig, IntoSystemConfigs, NextState, OnEnter, OnExit, OnUpdate, Or, Plugin,
+        Query, Res, ResMut, Transform, Vec3, With,
     },
     text::{Text, Text2dBundle, TextAlignment, TextSection, TextStyle},
     time::Time,
 };
 
 use crate::{
-    components::{PausedText, WelcomeText},
-    resource::{GameState, GameTextures},
+    action::{Action, ActionState},
+    bunker::BunkerCell,
+    components::{
+        Enemy, GameOverText, HighScoreText, Laser, NameEntryText, PausedText, WaveBanner,
+        WelcomeText,
+    },
+    enemy::boss::{Boss, BossHealthBar},
+    highscore::{today, HighScoreEntry, HighScores, NameEntry},
+    powerup::PowerUpKind,
+    resource::{GameData, GameState, GameTextures, MaxEnemy, PlayerState},
 };
 
 pub struct StatePlugin;
@@ -27,15 +36,33 @@ impl Plugin for StatePlugin {
                     .in_set(OnUpdate(GameState::Welcome)),
             )
             .add_system(welcome_exit_system.in_schedule(OnExit(GameState::Welcome)))
+            // InGame 状态下执行的函数
+            .add_system(in_game_input_system.in_set(OnUpdate(GameState::InGame)))
             // Paused 状态下执行的函数
             .add_system(paused_system.in_schedule(OnEnter(GameState::Paused)))
             .add_system(paused_input_system.in_set(OnUpdate(GameState::Paused)))
-            .add_system(paused_exit_system.in_schedule(OnExit(GameState::Paused)));
+            .add_system(paused_exit_system.in_schedule(OnExit(GameState::Paused)))
+            // GameOver 状态下执行的函数
+            .add_system(game_over_system.in_schedule(OnEnter(GameState::GameOver)))
+            .add_system(game_over_input_system.in_set(OnUpdate(GameState::GameOver)))
+            .add_system(game_over_exit_system.in_schedule(OnExit(GameState::GameOver)))
+            // NameEntry 状态下执行的函数
+            .add_system(name_entry_system.in_schedule(OnEnter(GameState::NameEntry)))
+            .add_systems(
+                (name_entry_input_system, name_entry_text_system)
+                    .chain()
+                    .in_set(OnUpdate(GameState::NameEntry)),
+            )
+            .add_system(name_entry_exit_system.in_schedule(OnExit(GameState::NameEntry)));
     }
 }
 
 /// 欢迎状态下运行的系统
-pub fn welcome_system(mut commands: Commands, game_textures: Res<GameTextures>) {
+pub fn welcome_system(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    high_scores: Res<HighScores>,
+) {
     // 字体引入
     let font = game_textures.font.clone();
     let text_style = TextStyle {
@@ -75,7 +102,7 @@ pub fn welcome_system(mut commands: Commands, game_textures: Res<GameTextures>)
             transform: Transform {
                 translation: Vec3 {
                     x: 0.,
-                    y: -20.,
+                    y: -120.,
                     z: 11.,
                 },
                 ..Default::default()
@@ -84,11 +111,53 @@ pub fn welcome_system(mut commands: Commands, game_textures: Res<GameTextures>)
         },
         WelcomeText,
     ));
+
+    // 排行榜
+    let mut table = String::from("HIGH SCORES\r\n");
+    if high_scores.entries().is_empty() {
+        table.push_str("NO RECORDS YET");
+    }
+    for (rank, entry) in high_scores.entries().iter().enumerate() {
+        table.push_str(&format!(
+            "{:>2}. {} {:>6} W{:<2} {}\r\n",
+            rank + 1,
+            entry.name,
+            entry.score,
+            entry.wave,
+            entry.date
+        ));
+    }
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                table,
+                TextStyle {
+                    font,
+                    font_size: 20.,
+                    color: Color::ANTIQUE_WHITE,
+                },
+            )
+            .with_alignment(text_alignment),
+            transform: Transform {
+                translation: Vec3 {
+                    x: 0.,
+                    y: 150.,
+                    z: 11.,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        HighScoreText,
+    ));
 }
 
-/// 欢迎状态状态下的键盘监听系统
-pub fn welcome_input_system(kb: Res<Input<KeyCode>>, mut next_state: ResMut<NextState<GameState>>) {
-    if kb.just_pressed(KeyCode::Return) {
+/// 欢迎状态状态下的输入监听系统
+pub fn welcome_input_system(
+    actions: Res<ActionState>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if actions.just_pressed(Action::Confirm) {
         next_state.set(GameState::InGame);
     }
 }
@@ -103,14 +172,29 @@ pub fn welcome_text_scale_system(
     }
 }
 
-/// 退出欢迎状态时执行的系统
+/// 退出欢迎状态时执行的系统，开始新的一局
+#[allow(clippy::type_complexity)]
 pub fn welcome_exit_system(
     mut commands: Commands,
-    query: Query<Entity, (With<Text>, With<WelcomeText>)>,
+    mut game_data: ResMut<GameData>,
+    query: Query<Entity, (With<Text>, Or<(With<WelcomeText>, With<HighScoreText>)>)>,
 ) {
     for entity in query.iter() {
         commands.entity(entity).despawn();
     }
+    game_data.reset();
+}
+
+/// 游戏状态下的输入监听系统
+///
+/// 不依赖玩家实体，玩家死亡或重生期间同样可以暂停，手柄断开时的自动暂停也不会丢失
+pub fn in_game_input_system(
+    actions: Res<ActionState>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if actions.just_pressed(Action::Pause) {
+        next_state.set(GameState::Paused);
+    }
 }
 
 /// 暂停状态下运行的系统
@@ -156,9 +240,14 @@ pub fn paused_system(mut commands: Commands, game_textures: Res<GameTextures>) {
     ));
 }
 
-/// 暂停状态状态下的键盘监听系统
-pub fn paused_input_system(kb: Res<Input<KeyCode>>, mut next_state: ResMut<NextState<GameState>>) {
-    if kb.pressed(KeyCode::R) {
+/// 暂停状态状态下的输入监听系统
+///
+/// 手柄的暂停与继续是同一个按键，只响应刚按下，避免暂停的同一次按键立即继续
+pub fn paused_input_system(
+    actions: Res<ActionState>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if actions.just_pressed(Action::Resume) {
         next_state.set(GameState::InGame);
     }
 }
@@ -172,3 +261,210 @@ pub fn paused_exit_system(
         commands.entity(entity).despawn();
     }
 }
+
+/// 游戏结束状态下运行的系统，展示最终得分
+pub fn game_over_system(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    game_data: Res<GameData>,
+) {
+    // 字体引入
+    let font = game_textures.font.clone();
+    let text_style = TextStyle {
+        font: font.clone(),
+        font_size: 46.,
+        color: Color::BLUE,
+    };
+    let text_alignment = TextAlignment::Center;
+
+    let text = Text {
+        sections: vec![
+            TextSection::new(
+                "GAME OVER!\r\n",
+                TextStyle {
+                    color: Color::RED,
+                    ..text_style.clone()
+                },
+            ),
+            TextSection::new(
+                format!("SCORE:{}\r\n", game_data.get_score()),
+                text_style.clone(),
+            ),
+            TextSection::new("PRESS ", text_style.clone()),
+            TextSection::new(
+                " ENTER ",
+                TextStyle {
+                    color: Color::RED,
+                    ..text_style.clone()
+                },
+            ),
+            TextSection::new("CONTINUE!", text_style.clone()),
+        ],
+        ..Default::default()
+    }
+    .with_alignment(text_alignment);
+    commands.spawn((
+        Text2dBundle {
+            text,
+            transform: Transform {
+                translation: Vec3 {
+                    x: 0.,
+                    y: -20.,
+                    z: 11.,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        GameOverText,
+    ));
+}
+
+/// 游戏结束状态下的输入监听系统，得分进入排行榜时进入名字输入
+pub fn game_over_input_system(
+    actions: Res<ActionState>,
+    game_data: Res<GameData>,
+    high_scores: Res<HighScores>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if actions.just_pressed(Action::Confirm) {
+        if high_scores.qualifies(game_data.get_score()) {
+            next_state.set(GameState::NameEntry);
+        } else {
+            next_state.set(GameState::Welcome);
+        }
+    }
+}
+
+/// 退出游戏结束状态时执行的系统，清理战场，准备下一局
+#[allow(clippy::type_complexity)]
+pub fn game_over_exit_system(
+    mut commands: Commands,
+    mut player_state: ResMut<PlayerState>,
+    mut max_enemy: ResMut<MaxEnemy>,
+    query: Query<
+        Entity,
+        Or<(
+            (With<Text>, With<GameOverText>),
+            With<Enemy>,
+            With<Laser>,
+            With<WaveBanner>,
+            With<PowerUpKind>,
+            With<BunkerCell>,
+            With<Boss>,
+            With<BossHealthBar>,
+        )>,
+    >,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    *player_state = PlayerState::default();
+    max_enemy.0 = 0;
+}
+
+/// 名字输入状态下运行的系统
+pub fn name_entry_system(mut commands: Commands) {
+    commands.insert_resource(NameEntry::default());
+    // 文字内容由 name_entry_text_system 更新
+    commands.spawn((
+        Text2dBundle {
+            transform: Transform {
+                translation: Vec3 {
+                    x: 0.,
+                    y: -20.,
+                    z: 11.,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        NameEntryText,
+    ));
+}
+
+/// 名字输入状态下的输入监听系统
+///
+/// 上下切换字母，左右移动光标，确认后保存到排行榜
+pub fn name_entry_input_system(
+    actions: Res<ActionState>,
+    game_data: Res<GameData>,
+    mut name_entry: ResMut<NameEntry>,
+    mut high_scores: ResMut<HighScores>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if actions.just_pressed(Action::Up) {
+        name_entry.cycle_letter(1);
+    } else if actions.just_pressed(Action::Down) {
+        name_entry.cycle_letter(-1);
+    } else if actions.just_pressed(Action::Left) {
+        name_entry.move_cursor(-1);
+    } else if actions.just_pressed(Action::Right) {
+        name_entry.move_cursor(1);
+    } else if actions.just_pressed(Action::Confirm) {
+        high_scores.insert(HighScoreEntry {
+            name: name_entry.name(),
+            score: game_data.get_score(),
+            wave: game_data.get_wave(),
+            date: today(),
+        });
+        if let Err(err) = high_scores.save() {
+            error!("failed to save high scores: {err}");
+        }
+        next_state.set(GameState::Welcome);
+    }
+}
+
+/// 名字输入文字更新系统，当前光标处的字母以红色显示
+pub fn name_entry_text_system(
+    game_textures: Res<GameTextures>,
+    game_data: Res<GameData>,
+    name_entry: Res<NameEntry>,
+    mut query: Query<&mut Text, With<NameEntryText>>,
+) {
+    if !name_entry.is_changed() {
+        return;
+    }
+
+    // 字体引入
+    let font = game_textures.font.clone();
+    let text_style = TextStyle {
+        font: font.clone(),
+        font_size: 46.,
+        color: Color::BLUE,
+    };
+
+    let mut sections = vec![TextSection::new(
+        format!("NEW HIGH SCORE!\r\n{}\r\n", game_data.get_score()),
+        text_style.clone(),
+    )];
+    for (index, letter) in name_entry.letters.iter().enumerate() {
+        let color = if index == name_entry.cursor {
+            Color::RED
+        } else {
+            Color::ANTIQUE_WHITE
+        };
+        sections.push(TextSection::new(
+            format!(" {} ", *letter as char),
+            TextStyle {
+                color,
+                ..text_style.clone()
+            },
+        ));
+    }
+
+    for mut text in &mut query {
+        text.sections = sections.clone();
+    }
+}
+
+/// 退出名字输入状态时执行的系统
+pub fn name_entry_exit_system(
+    mut commands: Commands,
+    query: Query<Entity, (With<Text>, With<NameEntryText>)>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+    commands.remove_resource::<NameEntry>();
+}
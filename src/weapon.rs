@@ -0,0 +1,325 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collision::shape::ColliderShape,
+    components::{
+        Damage, Enemy, FireControl, FromPlayer, Homing, HudAnchor, Laser, Movable, Piercing,
+        Player, SpriteSize, Team, Velocity, WeaponText,
+    },
+    config::{ChargeShotConfig, GameConfig, SpriteConfig},
+    powerup::{ActivePowerUps, PowerUpKind},
+    resource::GameTextures,
+    GameSet,
+};
+
+/// 武器类型组件
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WeaponKind {
+    /// 双发激光
+    Twin,
+    /// 扇形散射
+    Spread,
+    /// 穿透光束
+    Beam,
+    /// 追踪导弹
+    Homing,
+    /// 敌人单发激光
+    EnemySingle,
+    /// 敌人双发激光
+    EnemyTwin,
+}
+
+impl WeaponKind {
+    /// 全部武器类型
+    pub const ALL: [WeaponKind; 6] = [
+        WeaponKind::Twin,
+        WeaponKind::Spread,
+        WeaponKind::Beam,
+        WeaponKind::Homing,
+        WeaponKind::EnemySingle,
+        WeaponKind::EnemyTwin,
+    ];
+
+    /// 玩家可以通过按键切换的武器，按切换顺序排列
+    pub const PLAYER: [WeaponKind; 4] = [
+        WeaponKind::Twin,
+        WeaponKind::Spread,
+        WeaponKind::Beam,
+        WeaponKind::Homing,
+    ];
+
+    /// 配置文件中对应的字段名
+    pub fn config_key(&self) -> &'static str {
+        match self {
+            WeaponKind::Twin => "twin",
+            WeaponKind::Spread => "spread",
+            WeaponKind::Beam => "beam",
+            WeaponKind::Homing => "homing",
+            WeaponKind::EnemySingle => "enemy_single",
+            WeaponKind::EnemyTwin => "enemy_twin",
+        }
+    }
+
+    /// HUD 中展示的名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            WeaponKind::Twin => "TWIN",
+            WeaponKind::Spread => "SPREAD",
+            WeaponKind::Beam => "BEAM",
+            WeaponKind::Homing => "HOMING",
+            WeaponKind::EnemySingle => "SINGLE",
+            WeaponKind::EnemyTwin => "ENEMY TWIN",
+        }
+    }
+
+    /// 切换到的下一种玩家武器
+    pub fn next(&self) -> Self {
+        let index = WeaponKind::PLAYER.iter().position(|kind| kind == self);
+        match index {
+            Some(index) => WeaponKind::PLAYER[(index + 1) % WeaponKind::PLAYER.len()],
+            None => WeaponKind::Twin,
+        }
+    }
+}
+
+/// 武器组件，描述每次发射的弹道
+#[derive(Component, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Weapon {
+    /// 弹道图片，按 `size` 拉伸
+    pub sprite: SpriteConfig,
+    /// 每次发射的弹道数量
+    pub count: u32,
+    /// 相邻弹道之间的横向间距
+    pub spacing: f32,
+    /// 扇形弹道两端的夹角 (度)
+    pub spread: f32,
+    /// 速度，相对基础速度的倍数
+    pub speed: f32,
+    /// 伤害
+    pub damage: u32,
+    /// 冷却时间 (秒)
+    pub cooldown: f32,
+    /// 可以穿透的目标数量
+    #[serde(default)]
+    pub pierce: u32,
+    /// 追踪时每秒最大转向角度 (度)，为 0 时不追踪
+    #[serde(default)]
+    pub homing: f32,
+}
+
+impl Weapon {
+    /// 蓄力后的武器，`level` 为蓄力程度 (0 到 1)，伤害与弹道大小按比例放大
+    pub fn charged(&self, config: &ChargeShotConfig, level: f32) -> Weapon {
+        let damage_scale = 1. + (config.damage_scale - 1.) * level;
+        let size_scale = 1. + (config.size_scale - 1.) * level;
+        Weapon {
+            sprite: self.sprite.scaled(size_scale),
+            damage: (self.damage as f32 * damage_scale).round() as u32,
+            ..self.clone()
+        }
+    }
+
+    /// 发射弹道
+    ///
+    /// `angle` 为发射方向与正上方的夹角 (弧度)，向右为正，`team` 为弹道所属阵营的组件，决定弹道的碰撞层
+    pub fn fire<T: Team>(
+        &self,
+        commands: &mut Commands,
+        texture: Handle<Image>,
+        origin: Vec3,
+        angle: f32,
+        scale: f32,
+        team: T,
+    ) {
+        let size = Vec2::from(self.sprite.size);
+        let count = self.count.max(1);
+        let spread = self.spread.to_radians();
+
+        for i in 0..count {
+            // 以中间的弹道为基准向两侧排列
+            let t = i as f32 - (count - 1) as f32 / 2.;
+            let angle = if count > 1 {
+                angle + t * spread / (count - 1) as f32
+            } else {
+                angle
+            };
+            let rotation = Quat::from_rotation_z(-angle);
+            let offset = rotation * Vec3::new(t * self.spacing, 0., 0.);
+
+            let mut laser = commands.spawn(SpriteBundle {
+                texture: texture.clone(),
+                sprite: Sprite {
+                    custom_size: Some(size),
+                    ..Default::default()
+                },
+                transform: Transform {
+                    translation: origin + offset,
+                    rotation,
+                    scale: Vec3::new(scale, scale, 1.),
+                },
+                ..Default::default()
+            });
+            laser
+                .insert(Laser)
+                .insert(team)
+                .insert(T::COLLIDER)
+                .insert(ColliderShape::geometric(self.sprite.shape))
+                .insert(Damage(self.damage))
+                .insert(SpriteSize(size))
+                .insert(Movable { auto_despawn: true })
+                .insert(Velocity::new(
+                    angle.sin() * self.speed,
+                    angle.cos() * self.speed,
+                ));
+            if self.pierce > 0 {
+                laser.insert(Piercing::new(self.pierce));
+            }
+            if self.homing > 0. {
+                laser.insert(Homing {
+                    turn_rate: self.homing.to_radians(),
+                });
+            }
+        }
+    }
+}
+
+pub struct WeaponPlugin;
+
+impl Plugin for WeaponPlugin {
+    fn build(&self, app: &mut App) {
+        // 追踪在玩家与敌人移动之后、弹道移动之前调整方向
+        app.add_system(
+            homing_system
+                .after(crate::player::player_movable_system)
+                .after(crate::enemy::enemy_movement_system)
+                .after(crate::enemy::grid::grid_march_system)
+                .after(crate::enemy::boss::hit_zone_follow_system)
+                .before(crate::laser_movable_system)
+                .in_set(GameSet::Movement)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        )
+        .add_startup_system(weapon_text_setup_system.in_base_set(StartupSet::PostStartup))
+        .add_system(weapon_text_system);
+    }
+}
+
+/// 玩家当前使用的武器，散射道具生效期间替换为散射武器
+pub fn effective_weapon<'a>(
+    config: &'a GameConfig,
+    active_power_ups: &ActivePowerUps,
+    weapon: &'a Weapon,
+    kind: WeaponKind,
+) -> (&'a Weapon, WeaponKind) {
+    if active_power_ups.is_active(PowerUpKind::SpreadShot) {
+        (config.weapons.get(WeaponKind::Spread), WeaponKind::Spread)
+    } else {
+        (weapon, kind)
+    }
+}
+
+/// 追踪系统，玩家弹道追踪最近的敌人，敌人弹道追踪玩家
+#[allow(clippy::type_complexity)]
+fn homing_system(
+    fixed_time: Res<FixedTime>,
+    mut laser_query: Query<
+        (&mut Velocity, &mut Transform, &Homing, Option<&FromPlayer>),
+        With<Laser>,
+    >,
+    enemy_query: Query<&Transform, (With<Enemy>, Without<Laser>)>,
+    player_query: Query<&Transform, (With<Player>, Without<Laser>)>,
+) {
+    let delta = fixed_time.period.as_secs_f32();
+    for (mut velocity, mut transform, homing, from_player) in laser_query.iter_mut() {
+        let position = transform.translation.truncate();
+        let target = if from_player.is_some() {
+            enemy_query
+                .iter()
+                .map(|tf| tf.translation.truncate())
+                .min_by(|a, b| {
+                    a.distance_squared(position)
+                        .total_cmp(&b.distance_squared(position))
+                })
+        } else {
+            player_query
+                .get_single()
+                .ok()
+                .map(|tf| tf.translation.truncate())
+        };
+        let Some(target) = target else {
+            continue;
+        };
+
+        // 当前方向与目标方向的夹角，限制在 (-PI, PI] 之间
+        let speed = Vec2::new(velocity.x, velocity.y).length();
+        let current = velocity.x.atan2(velocity.y);
+        let to_target = target - position;
+        let desired = to_target.x.atan2(to_target.y);
+        let mut diff = desired - current;
+        if diff > PI {
+            diff -= 2. * PI;
+        } else if diff <= -PI {
+            diff += 2. * PI;
+        }
+
+        let max_turn = homing.turn_rate * delta;
+        let angle = current + diff.clamp(-max_turn, max_turn);
+        velocity.x = angle.sin() * speed;
+        velocity.y = angle.cos() * speed;
+        transform.rotation = Quat::from_rotation_z(-angle);
+    }
+}
+
+/// 生成武器状态控件
+fn weapon_text_setup_system(mut commands: Commands, game_textures: Res<GameTextures>) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: game_textures.font.clone(),
+                    font_size: 18.,
+                    color: Color::ANTIQUE_WHITE,
+                },
+            )
+            .with_alignment(TextAlignment::Right),
+            transform: Transform::from_xyz(0., 0., 11.),
+            text_anchor: bevy::sprite::Anchor::BottomRight,
+            ..Default::default()
+        },
+        HudAnchor::new(Vec2::new(1., -1.), Vec2::new(-10., 20.)),
+        WeaponText,
+    ));
+}
+
+/// 武器状态更新系统，蓄力时展示蓄力程度，玩家不存在时不展示
+fn weapon_text_system(
+    config: Res<GameConfig>,
+    active_power_ups: Res<ActivePowerUps>,
+    player_query: Query<(&Weapon, &WeaponKind, &FireControl), With<Player>>,
+    mut query: Query<&mut Text, With<WeaponText>>,
+) {
+    let value = match player_query.get_single() {
+        Ok((weapon, &kind, fire_control)) => {
+            let (_, kind) = effective_weapon(&config, &active_power_ups, weapon, kind);
+            let charge = config
+                .charge_shot
+                .enabled
+                .then(|| config.charge_shot.level(fire_control.charge))
+                .flatten();
+            match charge {
+                Some(level) => format!("CHARGE {:.0}%\nWEAPON {}", level * 100., kind.label()),
+                None => format!("WEAPON {}", kind.label()),
+            }
+        }
+        Err(_) => String::new(),
+    };
+    for mut text in &mut query {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
@@ -0,0 +1,68 @@
+// 每个集成测试只用到其中一部分
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use invaders::{
+    components::{Enemy, Player},
+    resource::{GameData, GameState},
+    InvadersPlugin,
+};
+
+/// 无窗口模式的游戏，直接从 `InGame` 状态开始
+pub fn headless_app(plugin: InvadersPlugin) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins).add_plugin(plugin);
+    app.world
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::InGame);
+    app
+}
+
+/// 推进一帧，帧间隔固定为 `delta`
+///
+/// bevy 0.10 的 `TimeUpdateStrategy::ManualDuration` 在 `Instant::now()` 上累加，
+/// 仍然受真实时间影响，这里在上一帧的时刻上累加，保证帧间隔完全确定
+pub fn step(app: &mut App, delta: Duration) {
+    let time = app.world.resource::<Time>();
+    let last_update = time.last_update().unwrap_or_else(|| time.startup());
+    app.insert_resource(TimeUpdateStrategy::ManualInstant(last_update + delta));
+    app.update();
+}
+
+/// 游戏过程的快照
+#[derive(Debug, PartialEq)]
+pub struct Snapshot {
+    pub state: GameState,
+    pub game_data: GameData,
+    /// 敌人的位置，按坐标排序
+    pub enemies: Vec<Vec3>,
+    /// 玩家的位置
+    pub player: Option<Vec3>,
+}
+
+impl Snapshot {
+    pub fn take(app: &mut App) -> Self {
+        let mut enemies: Vec<Vec3> = app
+            .world
+            .query_filtered::<&Transform, With<Enemy>>()
+            .iter(&app.world)
+            .map(|tf| tf.translation)
+            .collect();
+        enemies.sort_by(|a, b| a.to_array().partial_cmp(&b.to_array()).unwrap());
+        let player = app
+            .world
+            .query_filtered::<&Transform, With<Player>>()
+            .iter(&app.world)
+            .next()
+            .map(|tf| tf.translation);
+
+        Self {
+            state: app.world.resource::<State<GameState>>().0,
+            game_data: app.world.resource::<GameData>().clone(),
+            enemies,
+            player,
+        }
+    }
+}
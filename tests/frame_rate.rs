@@ -0,0 +1,39 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{headless_app, step, Snapshot};
+use invaders::{config::GameConfig, InvadersPlugin};
+
+const SEED: u64 = 7;
+/// 模拟的总时长：1200.5 个固定步长，避开步长的整数倍，帧间隔的舍入误差不会改变步数
+const STEPS: f64 = 1200.5;
+
+/// 以给定帧率运行相同的模拟时长
+fn run_at(hz: u32) -> Snapshot {
+    let config = GameConfig::default();
+    let period = Duration::from_secs_f32(config.time_step);
+    let total = period.as_nanos() as f64 * STEPS;
+    let frames = (total / 1e9 * hz as f64).round() as u64;
+    let delta = Duration::from_nanos((total / frames as f64) as u64);
+
+    let mut app = headless_app(InvadersPlugin {
+        seed: Some(SEED),
+        config,
+        ..InvadersPlugin::headless()
+    });
+    for _ in 0..frames {
+        step(&mut app, delta);
+    }
+    Snapshot::take(&mut app)
+}
+
+#[test]
+fn result_is_independent_of_frame_rate() {
+    let expected = run_at(60);
+    assert!(!expected.enemies.is_empty(), "enemies should have spawned");
+
+    for hz in [30, 144] {
+        assert_eq!(run_at(hz), expected, "{hz} Hz differs from 60 Hz");
+    }
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use common::{headless_app, step, Snapshot};
+use invaders::{
+    components::{FromEnemy, Laser},
+    config::{GameConfig, GameMode},
+    InvadersPlugin,
+};
+
+#[test]
+fn grid_mode_without_enemy_fire() {
+    let config = GameConfig {
+        mode: GameMode::Grid,
+        enemy_fire_rate: 0.,
+        ..Default::default()
+    };
+    config.validate().unwrap();
+    let period = Duration::from_secs_f32(config.time_step);
+    let mut app = headless_app(InvadersPlugin {
+        seed: Some(3),
+        config,
+        ..InvadersPlugin::headless()
+    });
+
+    for _ in 0..600 {
+        step(&mut app, period);
+        let enemy_lasers = app
+            .world
+            .query_filtered::<(), (With<Laser>, With<FromEnemy>)>()
+            .iter(&app.world)
+            .count();
+        assert_eq!(enemy_lasers, 0);
+    }
+    assert!(
+        !Snapshot::take(&mut app).enemies.is_empty(),
+        "the grid should have spawned"
+    );
+}
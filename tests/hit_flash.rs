@@ -0,0 +1,67 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use common::{headless_app, step};
+use invaders::{
+    components::{HitFlash, Player},
+    config::GameConfig,
+    constants::{HIT_FLASH_COLOR, HIT_FLASH_SECONDS},
+    events::DamageEvent,
+    InvadersPlugin,
+};
+
+fn player_sprite(app: &mut App) -> Option<(Entity, Color, bool)> {
+    app.world
+        .query_filtered::<(Entity, &Sprite, Option<&HitFlash>), With<Player>>()
+        .iter(&app.world)
+        .next()
+        .map(|(entity, sprite, flash)| (entity, sprite.color, flash.is_some()))
+}
+
+#[test]
+fn two_hits_in_one_step_restore_the_colour() {
+    let config = GameConfig {
+        // 敌人不攻击，只有测试发送的伤害
+        enemy_fire_rate: 0.,
+        player_hit_points: 10,
+        ..Default::default()
+    };
+    let period = Duration::from_secs_f32(config.time_step);
+    let mut app = headless_app(InvadersPlugin {
+        seed: Some(1),
+        config,
+        ..InvadersPlugin::headless()
+    });
+
+    let mut player = None;
+    for _ in 0..120 {
+        step(&mut app, period);
+        player = player_sprite(&mut app);
+        if player.is_some() {
+            break;
+        }
+    }
+    let (entity, color, _) = player.expect("player should have spawned");
+    assert_ne!(color, HIT_FLASH_COLOR);
+
+    // 两次伤害在同一个固定步长内结算
+    for _ in 0..2 {
+        app.world.send_event(DamageEvent {
+            target: entity,
+            amount: 1,
+        });
+    }
+    step(&mut app, period);
+    assert_eq!(
+        player_sprite(&mut app),
+        Some((entity, HIT_FLASH_COLOR, true))
+    );
+
+    let frames = (HIT_FLASH_SECONDS / period.as_secs_f32()).ceil() as u32 + 2;
+    for _ in 0..frames {
+        step(&mut app, period);
+    }
+    assert_eq!(player_sprite(&mut app), Some((entity, color, false)));
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::{app::AppExit, prelude::*};
+use common::{headless_app, step, Snapshot};
+use invaders::{
+    action::{Action, ActionState, ActionSystem},
+    config::GameConfig,
+    replay::{Replay, ReplayPlugin, ReplaySystem},
+    InvadersPlugin,
+};
+
+const FRAMES: u32 = 1200;
+const SEED: u64 = 20240601;
+
+/// 脚本输入：左右往返移动，按节奏攻击，偶尔切换武器
+#[derive(Resource, Default)]
+struct Script {
+    frame: u32,
+    fire_held: bool,
+}
+
+fn script_system(mut script: ResMut<Script>, mut actions: ResMut<ActionState>) {
+    let frame = script.frame;
+    script.frame += 1;
+
+    let fire = frame % 40 < 25;
+    actions.set(
+        Action::Fire,
+        fire,
+        fire && !script.fire_held,
+        !fire && script.fire_held,
+    );
+    script.fire_held = fire;
+
+    let switch = frame % 300 == 150;
+    actions.set(Action::SwitchWeapon, switch, switch, false);
+
+    actions.set_axis((frame as f32 * 0.02).sin() * 1.2);
+}
+
+/// 所有实体的位置、旋转与缩放，按数值排序
+///
+/// 并行执行的系统预留实体编号的先后不固定，这里不比较实体编号
+fn transforms(app: &mut App) -> Vec<[f32; 10]> {
+    let mut transforms: Vec<[f32; 10]> = app
+        .world
+        .query::<&Transform>()
+        .iter(&app.world)
+        .map(|tf| {
+            let mut values = [0.; 10];
+            values[..3].copy_from_slice(&tf.translation.to_array());
+            values[3..7].copy_from_slice(&tf.rotation.to_array());
+            values[7..].copy_from_slice(&tf.scale.to_array());
+            values
+        })
+        .collect();
+    transforms.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    transforms
+}
+
+#[test]
+fn playback_reproduces_recording() {
+    let path = std::env::temp_dir().join(format!("invaders-replay-{}.invr", std::process::id()));
+    let config = GameConfig {
+        player_lives: 10,
+        ..Default::default()
+    };
+
+    // 录制
+    let mut app = headless_app(InvadersPlugin {
+        seed: Some(SEED),
+        config: config.clone(),
+        ..InvadersPlugin::headless()
+    });
+    app.add_plugin(ReplayPlugin::Record(path.clone()))
+        .init_resource::<Script>()
+        .add_system(
+            script_system
+                .after(ActionSystem)
+                .before(ReplaySystem)
+                .in_base_set(CoreSet::PreUpdate),
+        );
+    for _ in 0..FRAMES {
+        step(&mut app, Duration::from_micros(16_667));
+    }
+    // 退出时写入缓冲区，这一帧同样被录制
+    app.world.send_event(AppExit);
+    step(&mut app, Duration::from_micros(16_667));
+    let recorded = Snapshot::take(&mut app);
+    let recorded_transforms = transforms(&mut app);
+    assert!(recorded.game_data.get_score() > 0, "script should score");
+
+    // 回放
+    let replay = Replay::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(replay.seed, SEED);
+    assert_eq!(replay.config, config);
+    assert_eq!(replay.frames.len(), FRAMES as usize + 1);
+
+    let mut app = headless_app(InvadersPlugin {
+        seed: Some(replay.seed),
+        config: replay.config.clone(),
+        ..InvadersPlugin::headless()
+    });
+    app.add_plugin(ReplayPlugin::Playback(Box::new(replay)));
+    for _ in 0..=FRAMES {
+        app.update();
+    }
+
+    assert_eq!(Snapshot::take(&mut app), recorded);
+    assert_eq!(transforms(&mut app), recorded_transforms);
+}